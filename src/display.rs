@@ -0,0 +1,185 @@
+//! Formatting with flags: width, fill, alignment, sign, zero padding and precision.
+//!
+//! Without precision, numbers are displayed exactly with trailing zeros stripped.
+//! A precision `{:.N}` rounds to `N` decimals half to even, `display_rounded` takes a mode:
+//!
+//! ```
+//! use fixnum::ops::RoundMode;
+//! use fixnum::FixedPoint;
+//!
+//! let value: FixedPoint<9> = "-1234.5678".parse()?;
+//!
+//! assert_eq!(format!("{}", value), "-1234.5678");
+//! assert_eq!(format!("{:.2}", value), "-1234.57");
+//! assert_eq!(format!("[{:>12.1}]", value), "[     -1234.6]");
+//! assert_eq!(format!("{:012.3}", value), "-0001234.568");
+//! assert_eq!(format!("{:+.0}", "1234.5".parse::<FixedPoint<9>>()?), "+1234");
+//!
+//! let ceil = value.display_rounded(2, RoundMode::Ceil).unwrap();
+//! assert_eq!(format!("[{:>10}]", ceil), "[  -1234.56]");
+//! assert!(value.display_rounded(2, RoundMode::Unnecessary).is_err());
+//! # Ok::<(), fixnum::ParseFixedPointError>(())
+//! ```
+
+use core::fmt::{self, Write};
+
+use crate::buffer::Buffer;
+use crate::ops::RoundMode;
+use crate::ArithmeticError;
+
+/// A number rounded to a fixed number of decimals, see `FixedPoint::display_rounded`.
+///
+/// Width, fill, alignment, sign and zero padding are honored, the precision is ignored.
+#[derive(Debug, Clone, Copy)]
+pub struct RoundedDisplay(Decimal);
+
+impl RoundedDisplay {
+    pub(crate) fn new(
+        mantissa: i128,
+        precision: u8,
+        decimals: u8,
+        mode: RoundMode,
+    ) -> Result<RoundedDisplay, ArithmeticError> {
+        Decimal::new(mantissa, precision, Some(usize::from(decimals)), mode).map(RoundedDisplay)
+    }
+}
+
+impl fmt::Display for RoundedDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A decimal number ready to be displayed: `digits / 10^scale` followed by `zeros` decimal zeros.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Decimal {
+    negative: bool,
+    digits: u128,
+    scale: usize,
+    pub(crate) zeros: usize,
+}
+
+impl Decimal {
+    /// Rounds `mantissa / 10^precision` to `decimals` with `mode`.
+    ///
+    /// Without `decimals`, the number is exact with trailing zeros stripped,
+    /// but integers keep one decimal, e.g. `5.0`.
+    pub(crate) fn new(
+        mantissa: i128,
+        precision: u8,
+        decimals: Option<usize>,
+        mode: RoundMode,
+    ) -> Result<Decimal, ArithmeticError> {
+        let negative = mantissa < 0;
+        let magnitude = mantissa.unsigned_abs();
+        let precision = usize::from(precision);
+
+        let (digits, scale, zeros) = match decimals {
+            Some(decimals) if decimals >= precision => (magnitude, precision, decimals - precision),
+            Some(decimals) => {
+                let divisor = 10u128.pow((precision - decimals) as u32);
+                let (mut digits, rest) = (magnitude / divisor, magnitude % divisor);
+
+                // Both fit `u128`, because the magnitude is at most `2^127`.
+                if rest != 0
+                    && mode.is_away_from_zero(
+                        negative,
+                        (rest * 2).cmp(&divisor),
+                        digits % 2 == 1,
+                    )?
+                {
+                    digits += 1;
+                }
+
+                (digits, decimals, 0)
+            }
+            None => {
+                let (mut digits, mut scale) = (magnitude, precision);
+
+                while scale > 0 && digits % 10 == 0 {
+                    digits /= 10;
+                    scale -= 1;
+                }
+
+                (digits, scale, usize::from(scale == 0))
+            }
+        };
+
+        Ok(Decimal {
+            negative,
+            digits,
+            scale,
+            zeros,
+        })
+    }
+
+    /// Values rounded to zero are displayed without the minus.
+    fn is_negative(&self) -> bool {
+        self.negative && self.digits != 0
+    }
+
+    pub(crate) fn sign<'a>(&self, f: &fmt::Formatter<'_>, minus: &'a str) -> &'a str {
+        match (self.is_negative(), f.sign_plus()) {
+            (true, _) => minus,
+            (false, true) => "+",
+            (false, false) => "",
+        }
+    }
+
+    pub(crate) fn integral(&self) -> u128 {
+        self.digits / 10u128.pow(self.scale as u32)
+    }
+
+    /// Writes the point and fractional digits, except trailing `zeros`.
+    pub(crate) fn write_fractional(&self, point: char, output: &mut impl Write) -> fmt::Result {
+        if self.scale + self.zeros > 0 {
+            output.write_char(point)?;
+        }
+
+        if self.scale > 0 {
+            let fractional = self.digits % 10u128.pow(self.scale as u32);
+            write!(output, "{:0scale$}", fractional, scale = self.scale)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the number according to flags of `f`, except the precision.
+    pub(crate) fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Enough for 39 digits and the point.
+        let mut body = Buffer::<48>::new();
+        write!(body, "{}", self.integral())?;
+        self.write_fractional('.', &mut body)?;
+        pad(f, self.sign(f, "-"), body.as_str(), self.zeros)
+    }
+}
+
+/// Writes `sign`, `body` and `zeros` trailing zeros, padded to the formatter's width.
+///
+/// Numbers are aligned to the right by default, zero padding goes after the sign.
+pub(crate) fn pad(f: &mut fmt::Formatter<'_>, sign: &str, body: &str, zeros: usize) -> fmt::Result {
+    let len = sign.chars().count() + body.chars().count() + zeros;
+    let padding = f.width().map_or(0, |width| width.saturating_sub(len));
+
+    let (before, zero_padding, after) = if f.sign_aware_zero_pad() {
+        (0, padding, 0)
+    } else {
+        match f.align() {
+            Some(fmt::Alignment::Left) => (0, 0, padding),
+            Some(fmt::Alignment::Center) => (padding / 2, 0, padding - padding / 2),
+            Some(fmt::Alignment::Right) | None => (padding, 0, 0),
+        }
+    };
+
+    let fill = f.fill();
+    repeat(f, fill, before)?;
+    f.write_str(sign)?;
+    repeat(f, '0', zero_padding)?;
+    f.write_str(body)?;
+    repeat(f, '0', zeros)?;
+    repeat(f, fill, after)
+}
+
+fn repeat(f: &mut fmt::Formatter<'_>, c: char, count: usize) -> fmt::Result {
+    (0..count).try_for_each(|_| f.write_char(c))
+}
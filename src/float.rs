@@ -0,0 +1,195 @@
+//! Exact conversions between floats and mantissas.
+//!
+//! Floats are decoded from their bits and encoded from the exact ratio `mantissa / COEF`,
+//! so every conversion rounds only once.
+
+use core::cmp::Ordering;
+use core::fmt::Write;
+use core::str;
+
+use crate::buffer::Buffer;
+use crate::i256::I256;
+use crate::mul_div::div_256;
+use crate::ops::RoundMode;
+use crate::parse::decimal_to_mantissa;
+use crate::ArithmeticError;
+
+/// Returns `value * coef` rounded to an integer according to `mode`.
+///
+/// NaN and infinities are reported as `Overflow`.
+pub(crate) fn to_mantissa(
+    value: f64,
+    coef: i128,
+    mode: RoundMode,
+) -> Result<i128, ArithmeticError> {
+    let bits = value.to_bits();
+    let negative = bits >> 63 == 1;
+    let biased = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+
+    if biased == 0x7ff {
+        return Err(ArithmeticError::Overflow);
+    }
+
+    // value = significand * 2^exp, subnormals have no implicit leading bit.
+    let (significand, exp) = if biased == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | 1 << 52, biased - 1075)
+    };
+
+    if significand == 0 {
+        return Ok(0);
+    }
+
+    let numerator = I256::from_i128(i128::from(significand)).mul(I256::from_i128(coef));
+    let numerator = if negative { numerator.neg() } else { numerator };
+
+    // The magnitude of `numerator` is below `2^size`.
+    let size = bit_length(u128::from(significand)) + bit_length(coef.unsigned_abs());
+
+    if exp >= 0 {
+        // Such values don't fit `i128` anyway.
+        if size + exp > 130 {
+            return Err(ArithmeticError::Overflow);
+        }
+
+        return numerator
+            .shl(exp as u32)
+            .to_i128()
+            .ok_or(ArithmeticError::Overflow);
+    }
+
+    if -exp > size {
+        // The exact value is below a half.
+        let away = mode.is_away_from_zero(negative, Ordering::Less, false)?;
+        return Ok(match (away, negative) {
+            (false, _) => 0,
+            (true, false) => 1,
+            (true, true) => -1,
+        });
+    }
+
+    div_256(numerator, I256::from_i128(1).shl(-exp as u32), mode)
+}
+
+/// Returns the mantissa of the shortest decimal representation of `value`,
+/// excess decimals are rounded with `mode`.
+pub(crate) fn shortest_to_mantissa(
+    value: f64,
+    precision: u8,
+    mode: RoundMode,
+) -> Result<i128, ArithmeticError> {
+    if !value.is_finite() {
+        return Err(ArithmeticError::Overflow);
+    }
+
+    // `{:e}` prints the shortest representation as `[-]d[.ddd]e[-]n`.
+    let mut buffer = Buffer::<32>::new();
+    write!(buffer, "{:e}", value).expect("the buffer is large enough");
+    let repr = buffer.as_bytes();
+
+    let negative = repr[0] == b'-';
+    let repr = &repr[usize::from(negative)..];
+    let split = repr
+        .iter()
+        .position(|&byte| byte == b'e')
+        .expect("no exponent");
+    let (significand, exponent) = (&repr[..split], &repr[split + 1..]);
+
+    let exponent: i64 = str::from_utf8(exponent)
+        .ok()
+        .and_then(|exponent| exponent.parse().ok())
+        .expect("invalid exponent");
+
+    let (integral, fractional) = match significand.iter().position(|&byte| byte == b'.') {
+        Some(point) => (&significand[..point], &significand[point + 1..]),
+        None => (significand, &[][..]),
+    };
+
+    decimal_to_mantissa(negative, integral, fractional, exponent, precision, mode)
+}
+
+/// Returns `mantissa / coef` correctly rounded to the nearest `f64`, ties to even.
+pub(crate) fn to_f64(mantissa: i128, coef: i128) -> f64 {
+    to_float(mantissa, coef, 53, -1022)
+}
+
+/// Returns `mantissa / coef` correctly rounded to the nearest `f32`, ties to even.
+pub(crate) fn to_f32(mantissa: i128, coef: i128) -> f32 {
+    // The result is representable as `f32`, so the cast is exact.
+    to_float(mantissa, coef, 24, -126) as f32
+}
+
+/// Rounds `mantissa / coef` to a float with the given significand size and minimal exponent.
+///
+/// The result is returned as `f64`, which represents such floats exactly.
+fn to_float(mantissa: i128, coef: i128, significand_bits: u32, min_exp: i32) -> f64 {
+    if mantissa == 0 {
+        return 0.0;
+    }
+
+    let p = significand_bits as i32;
+
+    // Scale by `2^shift` to get a quotient of `p` or `p + 1` bits,
+    // but keep the subnormal quantum for small values.
+    let shift = p + bit_length(coef.unsigned_abs()) - bit_length(mantissa.unsigned_abs());
+    let mut shift = shift.min(p - 1 - min_exp);
+
+    let numerator = I256::from_i128(mantissa).abs();
+    let denominator = I256::from_i128(coef).abs();
+    let (numerator, denominator) = if shift >= 0 {
+        (numerator.shl(shift as u32), denominator)
+    } else {
+        (numerator, denominator.shl(-shift as u32))
+    };
+
+    let (quotient, rest) = numerator.divrem(denominator);
+    let mut quotient = quotient.to_i128().expect("impossible") as u64;
+
+    // Compare the discarded part with a half of the last kept bit.
+    let half = if quotient >> p != 0 {
+        let dropped = quotient & 1;
+        quotient >>= 1;
+        shift -= 1;
+
+        match (dropped, rest.is_zero()) {
+            (0, true) => None,
+            (0, false) => Some(Ordering::Less),
+            (_, true) => Some(Ordering::Equal),
+            (_, false) => Some(Ordering::Greater),
+        }
+    } else if rest.is_zero() {
+        None
+    } else {
+        Some(rest.shl(1).compare(denominator))
+    };
+
+    let away = match half {
+        Some(Ordering::Greater) => true,
+        Some(Ordering::Equal) => quotient % 2 == 1,
+        _ => false,
+    };
+
+    if away {
+        quotient += 1;
+
+        if quotient >> p != 0 {
+            quotient >>= 1;
+            shift -= 1;
+        }
+    }
+
+    // Both factors are exact, so is the product.
+    let value = quotient as f64 * f64::from_bits(((1023 - shift) as u64) << 52);
+
+    if mantissa < 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+fn bit_length(value: u128) -> i32 {
+    (128 - value.leading_zeros()) as i32
+}
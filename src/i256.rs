@@ -0,0 +1,337 @@
+//! Signed 256-bit integer used as an intermediate type for 128-bit layouts.
+//!
+//! Only operations required by `FixedPoint128` are implemented.
+//! Operations are `const fn` to be usable in const arithmetic, operator traits delegate to them.
+
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Rem, Sub};
+
+/// Two's complement representation with little-endian limbs.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct I256([u64; 4]);
+
+impl I256 {
+    const ONE: I256 = I256([1, 0, 0, 0]);
+
+    #[inline]
+    pub(crate) const fn from_i128(value: i128) -> I256 {
+        let ext = if value < 0 { u64::MAX } else { 0 };
+        I256([value as u64, (value >> 64) as u64, ext, ext])
+    }
+
+    #[inline]
+    pub(crate) const fn to_i128(self) -> Option<i128> {
+        let result = (self.0[0] as i128) | ((self.0[1] as i128) << 64);
+        let ext = if result < 0 { u64::MAX } else { 0 };
+
+        if self.0[2] == ext && self.0[3] == ext {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the lowest 128 bits, i.e. wraps around if the value doesn't fit `i128`.
+    #[inline]
+    pub(crate) const fn wrapping_to_i128(self) -> i128 {
+        (self.0[0] as i128) | ((self.0[1] as i128) << 64)
+    }
+
+    #[inline]
+    pub(crate) const fn is_zero(self) -> bool {
+        self.0[0] | self.0[1] | self.0[2] | self.0[3] == 0
+    }
+
+    #[inline]
+    pub(crate) const fn is_negative(self) -> bool {
+        self.0[3] >> 63 == 1
+    }
+
+    #[inline]
+    pub(crate) const fn is_odd(self) -> bool {
+        self.0[0] & 1 == 1
+    }
+
+    #[inline]
+    pub(crate) const fn abs(self) -> I256 {
+        if self.is_negative() {
+            self.neg()
+        } else {
+            self
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn neg(self) -> I256 {
+        let inverted = I256([!self.0[0], !self.0[1], !self.0[2], !self.0[3]]);
+        inverted.add(I256::ONE)
+    }
+
+    /// Wrapping addition.
+    #[inline]
+    pub(crate) const fn add(self, rhs: I256) -> I256 {
+        let mut result = [0; 4];
+        let mut carry = false;
+        let mut i = 0;
+
+        while i < 4 {
+            let (sum, o1) = self.0[i].overflowing_add(rhs.0[i]);
+            let (sum, o2) = sum.overflowing_add(carry as u64);
+            result[i] = sum;
+            carry = o1 || o2;
+            i += 1;
+        }
+
+        I256(result)
+    }
+
+    /// Wrapping subtraction.
+    #[inline]
+    pub(crate) const fn sub(self, rhs: I256) -> I256 {
+        I256(usub(self.0, rhs.0))
+    }
+
+    /// Wrapping multiplication, it's enough for products of two 128-bit values.
+    #[inline]
+    pub(crate) const fn mul(self, rhs: I256) -> I256 {
+        let mut result = [0u64; 4];
+        let mut i = 0;
+
+        while i < 4 {
+            let mut carry = 0u128;
+            let mut j = 0;
+
+            while j < 4 - i {
+                let current =
+                    (self.0[i] as u128) * (rhs.0[j] as u128) + (result[i + j] as u128) + carry;
+                result[i + j] = current as u64;
+                carry = current >> 64;
+                j += 1;
+            }
+
+            i += 1;
+        }
+
+        I256(result)
+    }
+
+    /// Wrapping shift to the left, `bits` must be less than 256.
+    #[inline]
+    pub(crate) const fn shl(self, bits: u32) -> I256 {
+        let (limbs, bits) = ((bits / 64) as usize, bits % 64);
+        let mut result = [0u64; 4];
+        let mut i = limbs;
+
+        while i < 4 {
+            result[i] = self.0[i - limbs] << bits;
+
+            if bits > 0 && i > limbs {
+                result[i] |= self.0[i - limbs - 1] >> (64 - bits);
+            }
+
+            i += 1;
+        }
+
+        I256(result)
+    }
+
+    #[inline]
+    pub(crate) const fn compare(self, rhs: I256) -> Ordering {
+        match (self.is_negative(), rhs.is_negative()) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => ucmp(self.0, rhs.0),
+        }
+    }
+
+    /// Unsigned division of magnitudes.
+    const fn udivrem(numerator: [u64; 4], denominator: [u64; 4]) -> ([u64; 4], [u64; 4]) {
+        assert!(!I256(denominator).is_zero(), "attempt to divide by zero");
+
+        // Short division is enough for 64-bit divisors.
+        if denominator[1] == 0 && denominator[2] == 0 && denominator[3] == 0 {
+            let divisor = denominator[0] as u128;
+            let mut quotient = [0; 4];
+            let mut rem = 0u128;
+            let mut i = 4;
+
+            while i > 0 {
+                i -= 1;
+                let current = (rem << 64) | (numerator[i] as u128);
+                quotient[i] = (current / divisor) as u64;
+                rem = current % divisor;
+            }
+
+            return (quotient, [rem as u64, 0, 0, 0]);
+        }
+
+        let mut quotient = [0; 4];
+        let mut rem = [0u64; 4];
+        let mut bit = 256;
+
+        while bit > 0 {
+            bit -= 1;
+
+            // rem = (rem << 1) | bit
+            let mut i = 3;
+            while i > 0 {
+                rem[i] = (rem[i] << 1) | (rem[i - 1] >> 63);
+                i -= 1;
+            }
+            rem[0] = (rem[0] << 1) | ((numerator[bit / 64] >> (bit % 64)) & 1);
+
+            if !matches!(ucmp(rem, denominator), Ordering::Less) {
+                rem = usub(rem, denominator);
+                quotient[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+
+        (quotient, rem)
+    }
+
+    /// Truncating division, the same as for primitive integers.
+    pub(crate) const fn divrem(self, rhs: I256) -> (I256, I256) {
+        let (quotient, rem) = Self::udivrem(self.abs().0, rhs.abs().0);
+        let (quotient, rem) = (I256(quotient), I256(rem));
+
+        let quotient = if self.is_negative() != rhs.is_negative() {
+            quotient.neg()
+        } else {
+            quotient
+        };
+        let rem = if self.is_negative() { rem.neg() } else { rem };
+
+        (quotient, rem)
+    }
+}
+
+const fn ucmp(a: [u64; 4], b: [u64; 4]) -> Ordering {
+    let mut i = 4;
+
+    while i > 0 {
+        i -= 1;
+
+        if a[i] != b[i] {
+            return if a[i] < b[i] {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+        }
+    }
+
+    Ordering::Equal
+}
+
+const fn usub(a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
+    let mut result = [0; 4];
+    let mut borrow = false;
+    let mut i = 0;
+
+    while i < 4 {
+        let (diff, o1) = a[i].overflowing_sub(b[i]);
+        let (diff, o2) = diff.overflowing_sub(borrow as u64);
+        result[i] = diff;
+        borrow = o1 || o2;
+        i += 1;
+    }
+
+    result
+}
+
+impl From<i128> for I256 {
+    #[inline]
+    fn from(value: i128) -> I256 {
+        I256::from_i128(value)
+    }
+}
+
+impl From<i32> for I256 {
+    #[inline]
+    fn from(value: i32) -> I256 {
+        I256::from_i128(i128::from(value))
+    }
+}
+
+impl TryFrom<I256> for i128 {
+    type Error = ();
+
+    #[inline]
+    fn try_from(value: I256) -> Result<i128, ()> {
+        value.to_i128().ok_or(())
+    }
+}
+
+impl Ord for I256 {
+    fn cmp(&self, other: &I256) -> Ordering {
+        self.compare(*other)
+    }
+}
+
+impl PartialOrd for I256 {
+    fn partial_cmp(&self, other: &I256) -> Option<Ordering> {
+        Some(Ord::cmp(self, other))
+    }
+}
+
+impl Neg for I256 {
+    type Output = I256;
+
+    #[inline]
+    fn neg(self) -> I256 {
+        I256::neg(self)
+    }
+}
+
+impl Add for I256 {
+    type Output = I256;
+
+    #[inline]
+    fn add(self, rhs: I256) -> I256 {
+        I256::add(self, rhs)
+    }
+}
+
+impl AddAssign for I256 {
+    #[inline]
+    fn add_assign(&mut self, rhs: I256) {
+        *self = I256::add(*self, rhs);
+    }
+}
+
+impl Sub for I256 {
+    type Output = I256;
+
+    #[inline]
+    fn sub(self, rhs: I256) -> I256 {
+        I256::sub(self, rhs)
+    }
+}
+
+impl Mul for I256 {
+    type Output = I256;
+
+    #[inline]
+    fn mul(self, rhs: I256) -> I256 {
+        I256::mul(self, rhs)
+    }
+}
+
+impl Div for I256 {
+    type Output = I256;
+
+    #[inline]
+    fn div(self, rhs: I256) -> I256 {
+        self.divrem(rhs).0
+    }
+}
+
+impl Rem for I256 {
+    type Output = I256;
+
+    #[inline]
+    fn rem(self, rhs: I256) -> I256 {
+        self.divrem(rhs).1
+    }
+}
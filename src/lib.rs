@@ -1,438 +1,902 @@
-use std::convert::TryFrom;
-use std::str::FromStr;
-use std::{fmt, i64};
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use serde::{Deserialize, Serialize};
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "std")]
 use thiserror::Error;
 
+use crate::display::{Decimal, RoundedDisplay};
+use crate::i256::I256;
+use crate::locale::{Locale, LocaleFormat};
 use crate::ops::{
-    CheckedAdd, CheckedMul, CheckedSub, Numeric, RoundMode, RoundingDiv, RoundingMul,
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Numeric, RoundMode, RoundingDiv, RoundingMul,
+    SaturatingAdd, SaturatingDiv, SaturatingMul, SaturatingSub, WrappingAdd, WrappingDiv,
+    WrappingMul, WrappingSub,
 };
-
+use crate::parse::{fixed_point_from_str, ParseOptions};
+
+pub use crate::saturating::Saturating;
+
+mod buffer;
+pub mod display;
+mod float;
+mod i256;
+mod literal;
+pub mod locale;
+mod math;
+mod mul_div;
 pub mod ops;
+pub mod parse;
 mod power_table;
-#[cfg(test)]
+mod saturating;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "std-ops")]
+mod std_ops;
+// Tests rely on `std::error::Error` implementations.
+#[cfg(all(test, feature = "std"))]
 mod tests;
+mod uint;
 
-const COEF: i64 = 1_000_000_000;
-const COEF_128: i128 = COEF as i128;
-
-/// Abstraction over fixed point floating numbers.
-///
-/// The internal representation is a fixed point decimal number,
-/// i.e. a value pre-multiplied by 10^N, where N is a pre-defined number.
-#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-pub struct FixedPoint(i64);
-
-impl FixedPoint {
-    pub const EXP: i32 = -9;
-
-    pub const EPSILON: FixedPoint = FixedPoint(1);
-    pub const HALF: FixedPoint = FixedPoint(COEF / 2);
-    pub const MAX_MINUS_ONE: FixedPoint = FixedPoint(i64::MAX - 1);
-    pub const MINUS_ONE: FixedPoint = FixedPoint(-COEF);
-}
-
-#[derive(Debug, PartialEq, Error)]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "std", derive(Error))]
 pub enum ArithmeticError {
-    #[error("overflow")]
     Overflow,
-    #[error("division by zero")]
     DivisionByZero,
+    Inexact,
+    /// The argument is out of the function's domain, e.g. a square root of a negative value.
+    DomainViolation,
 }
 
-impl Numeric for FixedPoint {
-    const ZERO: FixedPoint = FixedPoint(0);
-    const ONE: FixedPoint = FixedPoint(COEF);
-    const MIN: FixedPoint = FixedPoint(i64::MIN);
-    const MAX: FixedPoint = FixedPoint(i64::MAX);
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ArithmeticError::Overflow => "overflow",
+            ArithmeticError::DivisionByZero => "division by zero",
+            ArithmeticError::Inexact => "inexact result",
+            ArithmeticError::DomainViolation => "argument is out of domain",
+        })
+    }
 }
 
-impl RoundingMul for FixedPoint {
-    type Output = FixedPoint;
-    type Error = ArithmeticError;
+macro_rules! impl_fixed_point {
+    (
+        $(#[$attr:meta])*
+        $name:ident,
+        layout = $layout:ident,
+        promotion = $promotion:ident,
+        mul_div = $mul_div:path,
+        wrapping_mul_div = $wrapping_mul_div:path,
+        max_precision = $max_precision:literal,
+        power_table = $power_table:path,
+        rounding_to_int = $rounding_to_int:ident
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name<const PRECISION: u8>($layout);
+
+        impl<const P: u8> $name<P> {
+            const COEF: $layout = {
+                assert!(
+                    P <= $max_precision,
+                    concat!("precision is too high for the ", stringify!($layout), " layout")
+                );
+                let ten: $layout = 10;
+                ten.pow(P as u32)
+            };
+
+            pub const EXP: i32 = -(P as i32);
+
+            pub const EPSILON: $name<P> = $name(1);
+            pub const HALF: $name<P> = {
+                assert!(P > 0, "a half isn't representable without decimals");
+                $name(Self::COEF / 2)
+            };
+            pub const MAX_MINUS_ONE: $name<P> = $name($layout::MAX - 1);
+            pub const MINUS_ONE: $name<P> = $name(-Self::COEF);
+        }
 
-    #[inline]
-    fn rmul(self, rhs: FixedPoint, mode: RoundMode) -> Result<FixedPoint, ArithmeticError> {
-        // TODO(loyd): avoid 128bit arithmetic when possible,
-        //      because LLVM doesn't replace 128bit division by const with multiplication.
+        impl<const P: u8> Numeric for $name<P> {
+            const ZERO: $name<P> = $name(0);
+            const ONE: $name<P> = $name(Self::COEF);
+            const MIN: $name<P> = $name($layout::MIN);
+            const MAX: $name<P> = $name($layout::MAX);
+        }
 
-        let value = i128::from(self.0) * i128::from(rhs.0);
-        let (mut result, loss) = (value / COEF_128, value % COEF_128);
+        impl<const P: u8> $name<P> {
+            /// The same as `CheckedAdd::cadd`, but usable in const contexts.
+            #[inline]
+            pub const fn const_add(self, rhs: $name<P>) -> Result<$name<P>, ArithmeticError> {
+                match self.0.checked_add(rhs.0) {
+                    Some(value) => Ok($name(value)),
+                    None => Err(ArithmeticError::Overflow),
+                }
+            }
 
-        if loss != 0 && mode as i32 == value.signum() as i32 {
-            result += value.signum();
-        }
+            /// The same as `CheckedSub::csub`, but usable in const contexts.
+            #[inline]
+            pub const fn const_sub(self, rhs: $name<P>) -> Result<$name<P>, ArithmeticError> {
+                match self.0.checked_sub(rhs.0) {
+                    Some(value) => Ok($name(value)),
+                    None => Err(ArithmeticError::Overflow),
+                }
+            }
 
-        if i128::from(result as i64) != result {
-            return Err(ArithmeticError::Overflow);
-        }
+            /// The same as `CheckedMul::<$layout>::cmul`, but usable in const contexts.
+            #[inline]
+            pub const fn const_mul(self, rhs: $layout) -> Result<$name<P>, ArithmeticError> {
+                match self.0.checked_mul(rhs) {
+                    Some(value) => Ok($name(value)),
+                    None => Err(ArithmeticError::Overflow),
+                }
+            }
 
-        Ok(FixedPoint(result as i64))
-    }
-}
+            /// The same as `RoundingMul::rmul`, but usable in const contexts.
+            #[inline]
+            pub const fn const_rmul(
+                self,
+                rhs: $name<P>,
+                mode: RoundMode,
+            ) -> Result<$name<P>, ArithmeticError> {
+                match $mul_div(self.0, rhs.0, Self::COEF, mode) {
+                    Ok(value) => Ok($name(value)),
+                    Err(err) => Err(err),
+                }
+            }
 
-impl RoundingDiv for FixedPoint {
-    type Output = FixedPoint;
-    type Error = ArithmeticError;
+            /// The same as `RoundingDiv::rdiv`, but usable in const contexts.
+            #[inline]
+            pub const fn const_rdiv(
+                self,
+                rhs: $name<P>,
+                mode: RoundMode,
+            ) -> Result<$name<P>, ArithmeticError> {
+                match $mul_div(self.0, Self::COEF, rhs.0, mode) {
+                    Ok(value) => Ok($name(value)),
+                    Err(err) => Err(err),
+                }
+            }
+
+            /// The same as `RoundingDiv::<$layout>::rdiv`, but usable in const contexts.
+            #[inline]
+            pub const fn const_rdiv_int(
+                self,
+                rhs: $layout,
+                mode: RoundMode,
+            ) -> Result<$name<P>, ArithmeticError> {
+                if rhs == 0 {
+                    return Err(ArithmeticError::DivisionByZero);
+                }
+
+                let mut result = match self.0.checked_div(rhs) {
+                    Some(result) => result,
+                    None => return Err(ArithmeticError::Overflow),
+                };
+                let loss = self.0 % rhs;
+
+                if loss != 0 {
+                    let negative = (self.0 < 0) != (rhs < 0);
+                    let loss = loss.unsigned_abs();
+                    let rest = rhs.unsigned_abs() - loss;
+                    let half = if loss < rest {
+                        Ordering::Less
+                    } else if loss > rest {
+                        Ordering::Greater
+                    } else {
+                        Ordering::Equal
+                    };
+
+                    match mode.is_away_from_zero(negative, half, result % 2 != 0) {
+                        Ok(true) => result += if negative { -1 } else { 1 },
+                        Ok(false) => {}
+                        Err(err) => return Err(err),
+                    }
+                }
+
+                Ok($name(result))
+            }
+        }
 
-    #[inline]
-    fn rdiv(self, rhs: FixedPoint, mode: RoundMode) -> Result<FixedPoint, ArithmeticError> {
-        // TODO(loyd): avoid 128bit arithmetic when possible,
-        //      because LLVM doesn't replace 128bit division by const with multiplication.
+        impl<const P: u8> RoundingMul for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
 
-        if rhs == FixedPoint::ZERO {
-            return Err(ArithmeticError::DivisionByZero);
+            #[inline]
+            fn rmul(self, rhs: $name<P>, mode: RoundMode) -> Result<$name<P>, ArithmeticError> {
+                self.const_rmul(rhs, mode)
+            }
         }
 
-        let numerator = i128::from(self.0) * COEF_128;
-        let denominator = i128::from(rhs.0);
+        impl<const P: u8> RoundingDiv for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
 
-        let (mut result, loss) = (numerator / denominator, numerator % denominator);
+            #[inline]
+            fn rdiv(self, rhs: $name<P>, mode: RoundMode) -> Result<$name<P>, ArithmeticError> {
+                self.const_rdiv(rhs, mode)
+            }
+        }
 
-        if loss != 0 {
-            let sign = numerator.signum() * denominator.signum();
+        impl<const P: u8> RoundingDiv<$layout> for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
 
-            if mode as i128 == sign {
-                result += sign;
+            #[inline]
+            fn rdiv(self, rhs: $layout, mode: RoundMode) -> Result<$name<P>, ArithmeticError> {
+                self.const_rdiv_int(rhs, mode)
             }
         }
 
-        if i128::from(result as i64) != result {
-            return Err(ArithmeticError::Overflow);
+        impl<const P: u8> CheckedAdd for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
+
+            #[inline]
+            fn cadd(self, rhs: $name<P>) -> Result<$name<P>, ArithmeticError> {
+                self.const_add(rhs)
+            }
         }
 
-        Ok(FixedPoint(result as i64))
-    }
-}
+        impl<const P: u8> CheckedSub for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
+
+            #[inline]
+            fn csub(self, rhs: $name<P>) -> Result<$name<P>, ArithmeticError> {
+                self.const_sub(rhs)
+            }
+        }
 
-impl RoundingDiv<i64> for FixedPoint {
-    type Output = FixedPoint;
-    type Error = ArithmeticError;
+        impl<const P: u8> CheckedMul<$layout> for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
 
-    #[inline]
-    fn rdiv(self, rhs: i64, mode: RoundMode) -> Result<FixedPoint, ArithmeticError> {
-        if rhs == 0 {
-            return Err(ArithmeticError::DivisionByZero);
+            #[inline]
+            fn cmul(self, rhs: $layout) -> Result<$name<P>, ArithmeticError> {
+                self.const_mul(rhs)
+            }
         }
 
-        let numerator = self.0;
-        let denominator = rhs;
+        /// Succeeds only if the product is exactly representable.
+        impl<const P: u8> CheckedMul for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
 
-        let (mut result, loss) = (numerator / denominator, numerator % denominator);
+            #[inline]
+            fn cmul(self, rhs: $name<P>) -> Result<$name<P>, ArithmeticError> {
+                self.rmul(rhs, RoundMode::Unnecessary)
+            }
+        }
 
-        if loss != 0 {
-            let sign = numerator.signum() * denominator.signum();
+        /// Succeeds only if the quotient is exactly representable.
+        impl<const P: u8> CheckedDiv for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
 
-            if mode as i64 == sign {
-                result += sign;
+            #[inline]
+            fn cdiv(self, rhs: $name<P>) -> Result<$name<P>, ArithmeticError> {
+                self.rdiv(rhs, RoundMode::Unnecessary)
             }
         }
 
-        Ok(FixedPoint(result as i64))
-    }
-}
+        /// Succeeds only if the quotient is exactly representable.
+        impl<const P: u8> CheckedDiv<$layout> for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
 
-impl CheckedAdd for FixedPoint {
-    type Output = FixedPoint;
-    type Error = ArithmeticError;
+            #[inline]
+            fn cdiv(self, rhs: $layout) -> Result<$name<P>, ArithmeticError> {
+                self.rdiv(rhs, RoundMode::Unnecessary)
+            }
+        }
 
-    #[inline]
-    fn cadd(self, rhs: FixedPoint) -> Result<FixedPoint, ArithmeticError> {
-        self.0
-            .checked_add(rhs.0)
-            .map(FixedPoint)
-            .ok_or(ArithmeticError::Overflow)
-    }
-}
+        impl<const P: u8> SaturatingAdd for $name<P> {
+            type Output = $name<P>;
 
-impl CheckedSub for FixedPoint {
-    type Output = FixedPoint;
-    type Error = ArithmeticError;
+            #[inline]
+            fn saturating_add(self, rhs: $name<P>) -> $name<P> {
+                $name(self.0.saturating_add(rhs.0))
+            }
+        }
 
-    #[inline]
-    fn csub(self, rhs: FixedPoint) -> Result<FixedPoint, ArithmeticError> {
-        self.0
-            .checked_sub(rhs.0)
-            .map(FixedPoint)
-            .ok_or(ArithmeticError::Overflow)
-    }
-}
+        impl<const P: u8> SaturatingSub for $name<P> {
+            type Output = $name<P>;
 
-impl CheckedMul<i64> for FixedPoint {
-    type Output = FixedPoint;
-    type Error = ArithmeticError;
+            #[inline]
+            fn saturating_sub(self, rhs: $name<P>) -> $name<P> {
+                $name(self.0.saturating_sub(rhs.0))
+            }
+        }
 
-    #[inline]
-    fn cmul(self, rhs: i64) -> Result<FixedPoint, ArithmeticError> {
-        self.0
-            .checked_mul(rhs)
-            .map(FixedPoint)
-            .ok_or(ArithmeticError::Overflow)
-    }
-}
+        impl<const P: u8> SaturatingMul for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
+
+            #[inline]
+            fn saturating_rmul(
+                self,
+                rhs: $name<P>,
+                mode: RoundMode,
+            ) -> Result<$name<P>, ArithmeticError> {
+                match self.rmul(rhs, mode) {
+                    Err(ArithmeticError::Overflow) if (self.0 < 0) != (rhs.0 < 0) => Ok(Self::MIN),
+                    Err(ArithmeticError::Overflow) => Ok(Self::MAX),
+                    result => result,
+                }
+            }
+        }
 
-impl FixedPoint {
-    #[inline]
-    pub fn recip(self, mode: RoundMode) -> Result<FixedPoint, ArithmeticError> {
-        Self::ONE.rdiv(self, mode)
-    }
+        impl<const P: u8> SaturatingDiv for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
+
+            #[inline]
+            fn saturating_rdiv(
+                self,
+                rhs: $name<P>,
+                mode: RoundMode,
+            ) -> Result<$name<P>, ArithmeticError> {
+                match self.rdiv(rhs, mode) {
+                    Err(ArithmeticError::Overflow) if (self.0 < 0) != (rhs.0 < 0) => Ok(Self::MIN),
+                    Err(ArithmeticError::Overflow) => Ok(Self::MAX),
+                    result => result,
+                }
+            }
+        }
 
-    #[inline]
-    pub fn cneg(self) -> Result<FixedPoint, ArithmeticError> {
-        self.0
-            .checked_neg()
-            .map(FixedPoint)
-            .ok_or_else(|| ArithmeticError::Overflow)
-    }
+        impl<const P: u8> WrappingAdd for $name<P> {
+            type Output = $name<P>;
 
-    #[inline]
-    pub fn half_sum(a: FixedPoint, b: FixedPoint) -> FixedPoint {
-        // TODO: optimize
-        let sum = i128::from(a.0) + i128::from(b.0);
-        FixedPoint((sum / 2) as i64)
-    }
+            #[inline]
+            fn wrapping_add(self, rhs: $name<P>) -> $name<P> {
+                $name(self.0.wrapping_add(rhs.0))
+            }
+        }
 
-    #[inline]
-    pub fn integral(self, mode: RoundMode) -> i64 {
-        let sign = self.0.signum();
-        let (int, frac) = (self.0 / COEF, self.0.abs() % COEF);
+        impl<const P: u8> WrappingSub for $name<P> {
+            type Output = $name<P>;
 
-        if mode as i64 == sign && frac > 0 {
-            int + sign
-        } else {
-            int
+            #[inline]
+            fn wrapping_sub(self, rhs: $name<P>) -> $name<P> {
+                $name(self.0.wrapping_sub(rhs.0))
+            }
         }
-    }
 
-    #[inline]
-    pub fn round_towards_zero_by(self, precision: FixedPoint) -> FixedPoint {
-        self.0
-            .checked_div(precision.0)
-            .and_then(|v| v.checked_mul(precision.0))
-            .map_or(self, FixedPoint)
-    }
+        impl<const P: u8> WrappingMul for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
+
+            #[inline]
+            fn wrapping_rmul(
+                self,
+                rhs: $name<P>,
+                mode: RoundMode,
+            ) -> Result<$name<P>, ArithmeticError> {
+                $wrapping_mul_div(self.0, rhs.0, Self::COEF, mode).map($name)
+            }
+        }
 
-    pub fn next_power_of_ten(self) -> Result<FixedPoint, ArithmeticError> {
-        if self < FixedPoint::ZERO {
-            return self.cneg()?.next_power_of_ten()?.cneg();
+        impl<const P: u8> WrappingDiv for $name<P> {
+            type Output = $name<P>;
+            type Error = ArithmeticError;
+
+            #[inline]
+            fn wrapping_rdiv(
+                self,
+                rhs: $name<P>,
+                mode: RoundMode,
+            ) -> Result<$name<P>, ArithmeticError> {
+                $wrapping_mul_div(self.0, Self::COEF, rhs.0, mode).map($name)
+            }
         }
 
-        let lz = self.0.leading_zeros() as usize;
-        assert!(lz > 0, "unexpected negative value");
+        impl<const P: u8> $name<P> {
+            #[inline]
+            pub fn recip(self, mode: RoundMode) -> Result<$name<P>, ArithmeticError> {
+                Self::ONE.rdiv(self, mode)
+            }
+
+            #[inline]
+            pub fn cneg(self) -> Result<$name<P>, ArithmeticError> {
+                self.0
+                    .checked_neg()
+                    .map($name)
+                    .ok_or(ArithmeticError::Overflow)
+            }
+
+            #[inline]
+            pub fn half_sum(
+                a: $name<P>,
+                b: $name<P>,
+                mode: RoundMode,
+            ) -> Result<$name<P>, ArithmeticError> {
+                // TODO: optimize
+                let sum = $promotion::from(a.0) + $promotion::from(b.0);
+                let two = $promotion::from(2);
+                let (mut result, loss) = (sum / two, sum % two);
+
+                if loss != $promotion::from(0) {
+                    let negative = sum.is_negative();
+                    let odd = result % two != $promotion::from(0);
+
+                    if mode.is_away_from_zero(negative, Ordering::Equal, odd)? {
+                        result += $promotion::from(if negative { -1 } else { 1 });
+                    }
+                }
+
+                Ok($name($layout::try_from(result).expect("impossible")))
+            }
+
+            #[inline]
+            pub fn integral(self, mode: RoundMode) -> Result<$layout, ArithmeticError> {
+                let (int, frac) = (self.0 / Self::COEF, self.0 % Self::COEF);
 
-        let value = power_table::POWER_TABLE[lz];
+                if frac == 0 {
+                    return Ok(int);
+                }
 
-        let value = if self.0 > value {
-            power_table::POWER_TABLE[lz - 1]
-        } else {
-            value
-        };
+                let negative = self.0 < 0;
+                let half = frac.abs().cmp(&(Self::COEF - frac.abs()));
+
+                if mode.is_away_from_zero(negative, half, int % 2 != 0)? {
+                    Ok(int + if negative { -1 } else { 1 })
+                } else {
+                    Ok(int)
+                }
+            }
+
+            #[inline]
+            pub fn round_towards_zero_by(self, precision: $name<P>) -> $name<P> {
+                self.0
+                    .checked_div(precision.0)
+                    .and_then(|v| v.checked_mul(precision.0))
+                    .map_or(self, $name)
+            }
+
+            pub fn next_power_of_ten(self) -> Result<$name<P>, ArithmeticError> {
+                if self < Self::ZERO {
+                    return self.cneg()?.next_power_of_ten()?.cneg();
+                }
+
+                let lz = self.0.leading_zeros() as usize;
+                assert!(lz > 0, "unexpected negative value");
+
+                let value = $power_table[lz];
+
+                let value = if self.0 > value {
+                    $power_table[lz - 1]
+                } else {
+                    value
+                };
+
+                if value == 0 {
+                    return Err(ArithmeticError::Overflow);
+                }
+
+                Ok($name(value))
+            }
+
+            /// Multiplies `value` by `COEF` in floating-point arithmetic and rounds the product,
+            /// so the value can be rounded twice. Use `from_f64` to round the exact value.
+            pub fn rounding_from_f64(value: f64, mode: RoundMode) -> Result<$name<P>, ArithmeticError> {
+                let x = mode.round_f64(value * Self::COEF as f64)?;
+                // `MAX as f64` is rounded up to `-MIN`, that doesn't fit.
+                if x >= ($layout::MIN as f64) && x < -($layout::MIN as f64) {
+                    Ok($name(x as $layout))
+                } else {
+                    Err(ArithmeticError::Overflow)
+                }
+            }
+
+            /// Converts the exact value of `value`, decoded from its bits, rounding it once.
+            /// Fails with `Overflow` for NaN, infinities and out-of-range values.
+            pub fn from_f64(value: f64, mode: RoundMode) -> Result<$name<P>, ArithmeticError> {
+                let mantissa = float::to_mantissa(value, Self::COEF as i128, mode)?;
+                $layout::try_from(mantissa)
+                    .map($name)
+                    .map_err(|_| ArithmeticError::Overflow)
+            }
+
+            /// Converts `value` if it's exactly representable, fails with `Inexact` otherwise.
+            pub fn try_from_f64_exact(value: f64) -> Result<$name<P>, ArithmeticError> {
+                Self::from_f64(value, RoundMode::Unnecessary)
+            }
+
+            /// The same as `from_f64`, every `f32` is exactly representable as `f64`.
+            pub fn from_f32(value: f32, mode: RoundMode) -> Result<$name<P>, ArithmeticError> {
+                Self::from_f64(f64::from(value), mode)
+            }
+
+            /// The same as `try_from_f64_exact`.
+            pub fn try_from_f32_exact(value: f32) -> Result<$name<P>, ArithmeticError> {
+                Self::from_f32(value, RoundMode::Unnecessary)
+            }
+
+            /// Converts the shortest decimal representation of `value`, i.e. the one printed
+            /// by `{}` and parsed back to the same float, so `0.3` becomes exactly `0.3`.
+            /// Decimals beyond the precision are rounded with `mode`.
+            pub fn from_f64_shortest(value: f64, mode: RoundMode) -> Result<$name<P>, ArithmeticError> {
+                let mantissa = float::shortest_to_mantissa(value, P, mode)?;
+                $layout::try_from(mantissa)
+                    .map($name)
+                    .map_err(|_| ArithmeticError::Overflow)
+            }
 
-        if value == 0 {
-            return Err(ArithmeticError::Overflow);
+            /// Returns the nearest `f64`, ties to even.
+            pub fn to_f64(self) -> f64 {
+                float::to_f64(self.0 as i128, Self::COEF as i128)
+            }
+
+            /// Returns the nearest `f32`, ties to even.
+            pub fn to_f32(self) -> f32 {
+                float::to_f32(self.0 as i128, Self::COEF as i128)
+            }
+
+            /// Rounds to the nearest integer, halves away from zero.
+            pub fn $rounding_to_int(self) -> $layout {
+                let (int, rest) = (self.0 / Self::COEF, self.0 % Self::COEF);
+
+                // Compared with the remainder to not overflow near the bounds.
+                if rest.abs() >= Self::COEF - Self::COEF / 2 {
+                    int + rest.signum()
+                } else {
+                    int
+                }
+            }
         }
 
-        Ok(FixedPoint(value))
-    }
+        impl<const P: u8> TryFrom<$name<P>> for $layout {
+            type Error = NotIntegerError<$name<P>>;
 
-    pub fn rounding_from_f64(value: f64) -> Result<FixedPoint, ArithmeticError> {
-        let x = (value * COEF as f64).round();
-        if x >= (i64::MIN as f64) && x <= (i64::MAX as f64) {
-            Ok(FixedPoint(x as i64))
-        } else {
-            Err(ArithmeticError::Overflow)
+            fn try_from(value: $name<P>) -> Result<Self, Self::Error> {
+                if value.0 % $name::<P>::COEF == 0 {
+                    Ok(value.0 / $name::<P>::COEF)
+                } else {
+                    Err(NotIntegerError(value))
+                }
+            }
         }
-    }
 
-    pub fn to_f64(self) -> f64 {
-        (self.0 as f64) / (COEF as f64)
-    }
+        impl<const P: u8> fmt::Debug for $name<P> {
+            /// The same as `Display`, including flags.
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(self, f)
+            }
+        }
 
-    pub fn rounding_to_i64(self) -> i64 {
-        let x = if self.0 > 0 {
-            self.0 + COEF / 2
-        } else {
-            self.0 - COEF / 2
-        };
-        x / COEF
-    }
-}
+        impl<const P: u8> fmt::Display for $name<P> {
+            /// Honors formatter flags, a precision rounds half to even, see `display`.
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Decimal::new(self.0 as i128, P, f.precision(), RoundMode::HalfEven)
+                    .expect("impossible")
+                    .fmt(f)
+            }
+        }
+
+        impl<const P: u8> $name<P> {
+            pub const fn from_decimal(
+                mantissa: $layout,
+                exponent: i32,
+            ) -> Result<$name<P>, FixedPointFromDecimalError> {
+                let shift = match exponent.checked_sub(Self::EXP) {
+                    Some(shift) if shift >= 0 => shift as u32,
+                    _ => return Err(FixedPointFromDecimalError::UnsupportedExponent),
+                };
+
+                let ten: $layout = 10;
+                let multiplier = match ten.checked_pow(shift) {
+                    Some(multiplier) => multiplier,
+                    None => return Err(FixedPointFromDecimalError::UnsupportedExponent),
+                };
+
+                match mantissa.checked_mul(multiplier) {
+                    Some(mantissa) => Ok($name(mantissa)),
+                    None => Err(FixedPointFromDecimalError::TooBigMantissa),
+                }
+            }
+
+            pub const fn from_mantissa(mantissa: $layout) -> $name<P> {
+                $name(mantissa)
+            }
+
+            pub const fn mantissa(self) -> $layout {
+                self.0
+            }
+
+            /// Parses a decimal number in notations enabled by `options`.
+            pub fn parse_with(s: &str, options: ParseOptions) -> Result<$name<P>, ParseFixedPointError> {
+                fixed_point_from_str(s, P, options)
+                    .and_then(|value| {
+                        $layout::try_from(value).map_err(|_| ParseFixedPointError::Overflow)
+                    })
+                    .map($name)
+            }
+
+            /// The same as `from_str`, but fractional digits beyond the precision
+            /// are rounded with `mode` instead of being rejected.
+            pub fn parse_rounding(s: &str, mode: RoundMode) -> Result<$name<P>, ParseFixedPointError> {
+                Self::parse_with(s, ParseOptions::new().rounding(mode))
+            }
+
+            /// The same as `parse_rounding` with `RoundMode::TowardZero`.
+            pub fn parse_truncating(s: &str) -> Result<$name<P>, ParseFixedPointError> {
+                Self::parse_rounding(s, RoundMode::TowardZero)
+            }
+
+            /// Parses a number written according to `locale`, e.g. by `format_locale`.
+            pub fn parse_locale(s: &str, locale: &Locale) -> Result<$name<P>, ParseFixedPointError> {
+                Self::parse_with(s, ParseOptions::strict().locale(*locale))
+            }
 
-#[derive(Debug, Error)]
-#[error("value is not an integer: {0}")]
-pub struct NotIntegerError(FixedPoint);
+            /// Returns a wrapper displaying the number according to `locale`.
+            pub fn format_locale(self, locale: &Locale) -> LocaleFormat {
+                LocaleFormat::new(self.0 as i128, P, *locale)
+            }
 
-impl TryFrom<FixedPoint> for i64 {
-    type Error = NotIntegerError;
+            /// Rounds to `decimals` decimal digits with `mode` for display, e.g. in fixed columns.
+            ///
+            /// Fails with `Inexact` if `mode` is `RoundMode::Unnecessary` and digits are dropped.
+            pub fn display_rounded(
+                self,
+                decimals: u8,
+                mode: RoundMode,
+            ) -> Result<RoundedDisplay, ArithmeticError> {
+                RoundedDisplay::new(self.0 as i128, P, decimals, mode)
+            }
 
-    fn try_from(value: FixedPoint) -> Result<Self, Self::Error> {
-        if value.0 % COEF == 0 {
-            Ok(value.0 / COEF)
-        } else {
-            Err(NotIntegerError(value))
+            /// Used by the `fixnum!` macro.
+            #[doc(hidden)]
+            pub const fn __from_literal(literal: &str) -> $name<P> {
+                let min = $layout::MIN as i128;
+                let max = $layout::MAX as i128;
+                $name(literal::parse(literal, P, min, max) as $layout)
+            }
         }
-    }
-}
 
-impl fmt::Debug for FixedPoint {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self)
-    }
-}
+        impl<const P: u8> TryFrom<$layout> for $name<P> {
+            type Error = ConvertError;
 
-impl fmt::Display for FixedPoint {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let sign = self.0.signum();
-        let integral = (self.0 / COEF).abs();
-        let mut fractional = (self.0 % COEF).abs();
-        let mut frac_width = if fractional > 0 {
-            -Self::EXP as usize
-        } else {
-            0
-        };
-
-        while fractional > 0 && fractional % 10 == 0 {
-            fractional /= 10;
-            frac_width -= 1;
+            fn try_from(value: $layout) -> Result<Self, Self::Error> {
+                value
+                    .checked_mul(Self::COEF)
+                    .ok_or(Self::Error::Overflow)
+                    .map($name)
+            }
         }
 
-        write!(
-            f,
-            "{}{}.{:0width$}",
-            if sign < 0 { "-" } else { "" },
-            integral,
-            fractional,
-            width = frac_width
-        )
-    }
-}
+        impl<const P: u8> FromStr for $name<P> {
+            type Err = ParseFixedPointError;
 
-impl FixedPoint {
-    pub fn from_decimal(
-        mantissa: i64,
-        exponent: i32,
-    ) -> Result<FixedPoint, FixedPointFromDecimalError> {
-        if exponent < Self::EXP || exponent > 10 {
-            return Err(FixedPointFromDecimalError::UnsupportedExponent);
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::parse_with(s, ParseOptions::new())
+            }
         }
+    };
+}
 
-        let multiplier = 10i64.pow((exponent - Self::EXP) as u32);
+impl_fixed_point!(
+    /// Abstraction over fixed point floating numbers.
+    ///
+    /// The internal representation is a fixed point decimal number,
+    /// i.e. a value pre-multiplied by 10^PRECISION.
+    ///
+    /// `PRECISION` is a number of decimal digits after the point and must fit into the `i64`
+    /// layout, i.e. be at most 18. Values with different precisions are different types and
+    /// can't be mixed.
+    FixedPoint,
+    layout = i64,
+    promotion = i128,
+    mul_div = mul_div::mul_div_64,
+    wrapping_mul_div = mul_div::wrapping_mul_div_64,
+    max_precision = 18,
+    power_table = power_table::POWER_TABLE,
+    rounding_to_int = rounding_to_i64
+);
+
+impl_fixed_point!(
+    /// The same as `FixedPoint`, but backed by `i128`.
+    ///
+    /// Suitable for values with up to 38 decimal digits, e.g. 18-decimal token amounts.
+    /// Multiplication and division use a 256-bit intermediate, so no precision is lost.
+    FixedPoint128,
+    layout = i128,
+    promotion = I256,
+    mul_div = mul_div::mul_div_128,
+    wrapping_mul_div = mul_div::wrapping_mul_div_128,
+    max_precision = 38,
+    power_table = power_table::POWER_TABLE_128,
+    rounding_to_int = rounding_to_i128
+);
+
+impl_fixed_point!(
+    /// The same as `FixedPoint`, but backed by `i32`.
+    ///
+    /// Suitable for memory-heavy storage of small values with up to 9 decimal digits.
+    /// Multiplication and division use a 64-bit intermediate.
+    FixedPoint32,
+    layout = i32,
+    promotion = i64,
+    mul_div = mul_div::mul_div_32,
+    wrapping_mul_div = mul_div::wrapping_mul_div_32,
+    max_precision = 9,
+    power_table = power_table::POWER_TABLE_32,
+    rounding_to_int = rounding_to_i32
+);
+
+impl<const P: u8> FixedPoint<P> {
+    const ASSERT_FROM_32BIT: () = assert!(P <= 9, "32-bit integers don't fit this precision");
+}
 
-        mantissa
-            .checked_mul(multiplier)
-            .map(FixedPoint)
-            .map_or_else(|| Err(FixedPointFromDecimalError::TooBigMantissa), Ok)
-    }
+impl<const P: u8> FixedPoint128<P> {
+    const ASSERT_FROM_32BIT: () = assert!(P <= 28, "32-bit integers don't fit this precision");
+    const ASSERT_FROM_64BIT: () = assert!(P <= 19, "64-bit integers don't fit this precision");
+}
 
-    pub fn from_mantissa(mantissa: i64) -> FixedPoint {
-        FixedPoint(mantissa)
-    }
+// Errors implement `Display` by hand to be available without `std`,
+// `thiserror` only adds `std::error::Error` on top.
+
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(Error))]
+pub struct NotIntegerError<T>(T);
 
-    pub fn mantissa(self) -> i64 {
-        self.0
+impl<T: fmt::Display> fmt::Display for NotIntegerError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "value is not an integer: {}", self.0)
     }
 }
 
-#[derive(Debug, PartialEq, Error)]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "std", derive(Error))]
 pub enum FixedPointFromDecimalError {
-    #[error("unsupported exponent")]
     UnsupportedExponent,
-    #[error("too big mantissa")]
     TooBigMantissa,
 }
 
-#[derive(Debug, PartialEq, Error)]
+impl fmt::Display for FixedPointFromDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FixedPointFromDecimalError::UnsupportedExponent => "unsupported exponent",
+            FixedPointFromDecimalError::TooBigMantissa => "too big mantissa",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "std", derive(Error))]
 pub enum ConvertError {
-    #[error("overflow")]
     Overflow,
-    #[error("other: {}", _0)]
-    Other(String),
 }
 
-impl TryFrom<i64> for FixedPoint {
-    type Error = ConvertError;
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConvertError::Overflow => "overflow",
+        })
+    }
+}
+
+/// An error returned by `FromStr`, byte positions are offsets in the original string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Error))]
+pub enum ParseFixedPointError {
+    /// The string is empty or contains only whitespace.
+    Empty,
+    /// An unexpected character, e.g. a letter or a second point.
+    InvalidDigit { position: usize },
+    /// No digits before the point or the exponent, e.g. `.5` or `-`.
+    MissingIntegralPart,
+    /// The value needs more fractional digits than the precision allows.
+    ExcessPrecision { digits: usize },
+    /// The value doesn't fit the type.
+    Overflow,
+}
 
-    fn try_from(value: i64) -> Result<Self, Self::Error> {
-        value
-            .checked_mul(COEF)
-            .ok_or(Self::Error::Overflow)
-            .map(FixedPoint)
+impl fmt::Display for ParseFixedPointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseFixedPointError::Empty => f.write_str("empty string"),
+            ParseFixedPointError::InvalidDigit { position } => {
+                write!(f, "invalid digit at byte {}", position)
+            }
+            ParseFixedPointError::MissingIntegralPart => f.write_str("missing integral part"),
+            ParseFixedPointError::ExcessPrecision { digits } => {
+                write!(f, "precision is too high: {} fractional digits", digits)
+            }
+            ParseFixedPointError::Overflow => f.write_str("overflow"),
+        }
     }
 }
 
 /// Returns `FixedPoint` corresponding to the integer `value`.
-impl From<i32> for FixedPoint {
+///
+/// Available only for precisions up to 9, other precisions fail to compile.
+impl<const P: u8> From<i32> for FixedPoint<P> {
     fn from(value: i32) -> Self {
-        FixedPoint(i64::from(value).checked_mul(COEF).expect("impossible"))
+        #[allow(clippy::let_unit_value)]
+        let () = Self::ASSERT_FROM_32BIT;
+        FixedPoint(
+            i64::from(value)
+                .checked_mul(Self::COEF)
+                .expect("impossible"),
+        )
     }
 }
 
 /// Returns `FixedPoint` corresponding to the integer `value`.
-impl From<u32> for FixedPoint {
+///
+/// Available only for precisions up to 9, other precisions fail to compile.
+impl<const P: u8> From<u32> for FixedPoint<P> {
     fn from(value: u32) -> Self {
-        FixedPoint(i64::from(value).checked_mul(COEF).expect("impossible"))
+        #[allow(clippy::let_unit_value)]
+        let () = Self::ASSERT_FROM_32BIT;
+        FixedPoint(
+            i64::from(value)
+                .checked_mul(Self::COEF)
+                .expect("impossible"),
+        )
     }
 }
 
-impl FromStr for FixedPoint {
-    type Err = ConvertError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fixed_point_from_str(s).map(FixedPoint)
+/// Returns `FixedPoint128` corresponding to the integer `value`.
+///
+/// Available only for precisions up to 28, other precisions fail to compile.
+impl<const P: u8> From<i32> for FixedPoint128<P> {
+    fn from(value: i32) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::ASSERT_FROM_32BIT;
+        FixedPoint128(
+            i128::from(value)
+                .checked_mul(Self::COEF)
+                .expect("impossible"),
+        )
     }
 }
 
-fn fixed_point_from_str(str: &str) -> Result<i64, ConvertError> {
-    let str = str.trim();
-
-    let index = match str.find('.') {
-        Some(index) => index,
-        None => {
-            let integral: i64 = str.parse().map_err(|_| {
-                ConvertError::Other(format!("can't parse integral part of {}", str))
-            })?;
-            return integral.checked_mul(COEF).ok_or(ConvertError::Overflow);
-        }
-    };
-
-    let integral: i64 = str[0..index]
-        .parse()
-        .map_err(|_| ConvertError::Other("can't parse integral part".to_string()))?;
-    let fractional_str = &str[index + 1..];
-
-    if !fractional_str.chars().all(|c| c.is_digit(10)) {
-        return Err(ConvertError::Other(format!(
-            "wrong {} fractional part can only contain digits",
-            str
-        )));
+/// Returns `FixedPoint128` corresponding to the integer `value`.
+///
+/// Available only for precisions up to 28, other precisions fail to compile.
+impl<const P: u8> From<u32> for FixedPoint128<P> {
+    fn from(value: u32) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::ASSERT_FROM_32BIT;
+        FixedPoint128(
+            i128::from(value)
+                .checked_mul(Self::COEF)
+                .expect("impossible"),
+        )
     }
+}
 
-    if fractional_str.len() > FixedPoint::EXP.abs() as usize {
-        return Err(ConvertError::Other(format!(
-            "precision of {} is too high",
-            str
-        )));
+/// Returns `FixedPoint128` corresponding to the integer `value`.
+///
+/// Available only for precisions up to 19, other precisions fail to compile.
+impl<const P: u8> From<i64> for FixedPoint128<P> {
+    fn from(value: i64) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::ASSERT_FROM_64BIT;
+        FixedPoint128(
+            i128::from(value)
+                .checked_mul(Self::COEF)
+                .expect("impossible"),
+        )
     }
+}
 
-    let exp = 10i64.pow(fractional_str.len() as u32);
-
-    if exp > COEF {
-        return Err(ConvertError::Other(format!(
-            "precision of {} is too high",
-            str
-        )));
+impl<const P: u8> From<FixedPoint32<P>> for FixedPoint<P> {
+    fn from(value: FixedPoint32<P>) -> Self {
+        FixedPoint(i64::from(value.0))
     }
+}
 
-    let fractional: i64 = fractional_str
-        .parse()
-        .map_err(|_| ConvertError::Other(format!("can't parse fractional part of {}", str)))?;
-
-    let final_integral = integral.checked_mul(COEF).ok_or(ConvertError::Overflow)?;
-    let signum = if str.as_bytes()[0] == b'-' { -1 } else { 1 };
-    let final_fractional = signum * COEF / exp * fractional;
+impl<const P: u8> TryFrom<FixedPoint<P>> for FixedPoint32<P> {
+    type Error = ConvertError;
 
-    final_integral
-        .checked_add(final_fractional)
-        .ok_or(ConvertError::Overflow)
+    fn try_from(value: FixedPoint<P>) -> Result<Self, Self::Error> {
+        i32::try_from(value.0)
+            .map(FixedPoint32)
+            .map_err(|_| ConvertError::Overflow)
+    }
 }
@@ -0,0 +1,105 @@
+//! Compile-time parsing of decimal literals, see the `fixnum!` macro.
+
+/// Creates a fixed-point number from a decimal literal at compile time.
+///
+/// Malformed literals, literals with more decimals than the precision allows and
+/// out-of-range values are rejected with a compile error.
+///
+/// The type is inferred from the context and defaults to `FixedPoint`,
+/// other types can be passed as the second argument.
+///
+/// ```
+/// use fixnum::{fixnum, FixedPoint, FixedPoint128};
+///
+/// const FEE: FixedPoint<9> = fixnum!("0.0025");
+/// let price: FixedPoint128<18> = fixnum!("-1.5", FixedPoint128);
+///
+/// assert_eq!(FEE, "0.0025".parse().unwrap());
+/// assert_eq!(price, "-1.5".parse().unwrap());
+/// ```
+///
+/// ```compile_fail
+/// use fixnum::{fixnum, FixedPoint};
+///
+/// const TOO_PRECISE: FixedPoint<2> = fixnum!("0.001");
+/// ```
+#[macro_export]
+macro_rules! fixnum {
+    ($value:literal) => {
+        const { $crate::FixedPoint::__from_literal($value) }
+    };
+    ($value:literal, $($name:ident)::+) => {
+        const { $($name)::+::__from_literal($value) }
+    };
+}
+
+/// Parses a decimal literal to a mantissa in `min..=max` for the given precision.
+///
+/// Panics, i.e. fails compilation in const contexts, if the literal is invalid.
+pub(crate) const fn parse(literal: &str, precision: u8, min: i128, max: i128) -> i128 {
+    let bytes = literal.as_bytes();
+    let negative = !bytes.is_empty() && bytes[0] == b'-';
+    let signed = !bytes.is_empty() && (negative || bytes[0] == b'+');
+    let mut i = signed as usize;
+
+    assert!(i < bytes.len(), "empty fixed-point literal");
+
+    // The mantissa is accumulated with its sign to support `MIN`.
+    let mut mantissa: i128 = 0;
+    let mut decimals: Option<u8> = None;
+    let mut has_digits = false;
+
+    while i < bytes.len() {
+        let byte = bytes[i];
+        i += 1;
+
+        if byte == b'.' {
+            assert!(
+                has_digits && decimals.is_none(),
+                "invalid fixed-point literal"
+            );
+            decimals = Some(0);
+            continue;
+        }
+
+        assert!(byte.is_ascii_digit(), "invalid fixed-point literal");
+        has_digits = true;
+
+        if let Some(count) = decimals {
+            assert!(
+                count < precision,
+                "too many decimals in fixed-point literal"
+            );
+            decimals = Some(count + 1);
+        }
+
+        let digit = (byte - b'0') as i128;
+        let next = match mantissa.checked_mul(10) {
+            Some(value) if negative => value.checked_sub(digit),
+            Some(value) => value.checked_add(digit),
+            None => None,
+        };
+
+        mantissa = match next {
+            Some(value) => value,
+            None => panic!("fixed-point literal is out of range"),
+        };
+    }
+
+    assert!(!matches!(decimals, Some(0)), "invalid fixed-point literal");
+
+    let decimals = match decimals {
+        Some(count) => count,
+        None => 0,
+    };
+
+    let scaled = match 10i128.checked_pow((precision - decimals) as u32) {
+        Some(multiplier) => mantissa.checked_mul(multiplier),
+        None => None,
+    };
+
+    match scaled {
+        Some(value) if value >= min && value <= max => value,
+        _ => panic!("fixed-point literal is out of range"),
+    }
+}
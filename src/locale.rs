@@ -0,0 +1,191 @@
+//! Locale-aware parsing and formatting, e.g. `1.234.567,89` or `12,34,567.5`.
+//!
+//! Formatting localizes the output of `Display`, parsing shares the core of `FromStr`,
+//! so `parse_locale` reads back exactly what `format_locale` writes with default flags.
+//!
+//! ```
+//! use fixnum::locale::Locale;
+//! use fixnum::FixedPoint;
+//!
+//! let german = Locale::new().decimal_separator(',').grouping('.', &[3]);
+//! let value: FixedPoint<9> = "-1234567.89".parse()?;
+//!
+//! assert_eq!(value.format_locale(&german).to_string(), "-1.234.567,89");
+//! assert_eq!(FixedPoint::parse_locale("-1.234.567,89", &german)?, value);
+//!
+//! let indian = Locale::new().grouping(',', &[3, 2]);
+//! assert_eq!(value.format_locale(&indian).to_string(), "-12,34,567.89");
+//! # Ok::<(), fixnum::ParseFixedPointError>(())
+//! ```
+
+use core::fmt::{self, Write};
+
+use crate::buffer::Buffer;
+use crate::display::{self, Decimal};
+use crate::ops::RoundMode;
+
+/// Describes how numbers are written: separators, grouping and the minus sign.
+///
+/// Grouping separators are optional when parsing, but must be placed correctly if present.
+///
+/// The separators and the minus sign must differ, otherwise setters panic, so
+/// the decimal separator is set first when swapping `.` and `,`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    pub(crate) decimal_separator: char,
+    pub(crate) grouping_separator: Option<char>,
+    pub(crate) grouping: &'static [u8],
+    pub(crate) minus_sign: char,
+}
+
+impl Locale {
+    /// The notation of `FromStr` and `Display`: `-1234567.89`.
+    pub const fn new() -> Locale {
+        Locale {
+            decimal_separator: '.',
+            grouping_separator: None,
+            grouping: &[],
+            minus_sign: '-',
+        }
+    }
+
+    /// Sets the separator between integral and fractional parts.
+    pub const fn decimal_separator(mut self, separator: char) -> Locale {
+        assert!(!separator.is_ascii_digit(), "a digit can't be a separator");
+        assert!(
+            !matches!(self.grouping_separator, Some(group) if group == separator),
+            "the decimal separator is the same as the grouping one"
+        );
+        assert!(
+            separator != self.minus_sign,
+            "the decimal separator is the minus sign"
+        );
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Groups digits of the integral part with `separator`.
+    ///
+    /// `sizes` are sizes of groups from right to left, the last one is repeated,
+    /// e.g. `&[3]` gives `1,234,567` and `&[3, 2]` gives `12,34,567`.
+    pub const fn grouping(mut self, separator: char, sizes: &'static [u8]) -> Locale {
+        assert!(!separator.is_ascii_digit(), "a digit can't be a separator");
+        assert!(
+            separator != self.decimal_separator,
+            "the grouping separator is the same as the decimal one"
+        );
+        assert!(
+            separator != self.minus_sign,
+            "the grouping separator is the minus sign"
+        );
+        assert!(!sizes.is_empty(), "grouping sizes are empty");
+
+        let mut i = 0;
+        while i < sizes.len() {
+            assert!(sizes[i] > 0, "grouping sizes must be positive");
+            i += 1;
+        }
+
+        self.grouping_separator = Some(separator);
+        self.grouping = sizes;
+        self
+    }
+
+    /// Sets the minus sign, e.g. `−` (`U+2212`).
+    pub const fn minus_sign(mut self, sign: char) -> Locale {
+        assert!(!sign.is_ascii_digit(), "a digit can't be a sign");
+        assert!(
+            sign != self.decimal_separator,
+            "the minus sign is the decimal separator"
+        );
+        assert!(
+            !matches!(self.grouping_separator, Some(group) if group == sign),
+            "the minus sign is the grouping separator"
+        );
+        self.minus_sign = sign;
+        self
+    }
+
+    /// Returns the size of the group with the given index counting from the right.
+    pub(crate) fn group_size(&self, index: usize) -> usize {
+        usize::from(self.grouping[index.min(self.grouping.len() - 1)])
+    }
+
+    /// Checks whether a group starts right before the last `digits` integral digits.
+    fn is_group_boundary(&self, digits: usize) -> bool {
+        let mut boundary = 0;
+        let mut group = 0;
+
+        while boundary < digits {
+            boundary += self.group_size(group);
+            group += 1;
+        }
+
+        boundary == digits
+    }
+
+    /// Writes integral digits separated into groups.
+    fn write_grouped(&self, integral: &str, output: &mut impl Write) -> fmt::Result {
+        for (index, digit) in integral.chars().enumerate() {
+            if let (true, Some(separator)) = (index > 0, self.grouping_separator) {
+                if self.is_group_boundary(integral.len() - index) {
+                    output.write_char(separator)?;
+                }
+            }
+
+            output.write_char(digit)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Locale {
+        Locale::new()
+    }
+}
+
+/// A number displayed according to a locale, see `FixedPoint::format_locale`.
+///
+/// Flags work like in `Display`, the width counts the whole localized string.
+#[derive(Debug, Clone, Copy)]
+pub struct LocaleFormat {
+    mantissa: i128,
+    precision: u8,
+    locale: Locale,
+}
+
+impl LocaleFormat {
+    pub(crate) fn new(mantissa: i128, precision: u8, locale: Locale) -> LocaleFormat {
+        LocaleFormat {
+            mantissa,
+            precision,
+            locale,
+        }
+    }
+}
+
+impl fmt::Display for LocaleFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimal = Decimal::new(
+            self.mantissa,
+            self.precision,
+            f.precision(),
+            RoundMode::HalfEven,
+        )
+        .expect("impossible");
+
+        let mut integral = Buffer::<48>::new();
+        write!(integral, "{}", decimal.integral())?;
+
+        // Enough for 39 digits with separators and the fractional part.
+        let mut body = Buffer::<256>::new();
+        self.locale.write_grouped(integral.as_str(), &mut body)?;
+        decimal.write_fractional(self.locale.decimal_separator, &mut body)?;
+
+        let mut minus = [0; 4];
+        let sign = decimal.sign(f, self.locale.minus_sign.encode_utf8(&mut minus));
+        display::pad(f, sign, body.as_str(), decimal.zeros)
+    }
+}
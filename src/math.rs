@@ -0,0 +1,779 @@
+//! Elementary functions computed in integer arithmetic.
+//!
+//! Results are deterministic, i.e. don't depend on the platform's floating-point unit,
+//! and rounded according to the given `RoundMode`.
+//!
+//! Functions are implemented for `FixedPoint`, `FixedPoint32` delegates to them.
+
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+
+use crate::i256::I256;
+use crate::mul_div::{div_256, mul_div_128};
+use crate::ops::{Numeric, RoundMode, RoundingMul};
+use crate::uint::Uint;
+use crate::{ArithmeticError, FixedPoint, FixedPoint32};
+
+/// Extra decimal digits kept by `rpow_precise`.
+const GUARD_DIGITS: u32 = 18;
+
+// Transcendental functions are computed with 36 decimal digits,
+// so the error is far below 1 ulp of any precision.
+const SCALE_DIGITS: u32 = 36;
+const SCALE: i128 = 1_000_000_000_000_000_000_000_000_000_000_000_000;
+const LN_2: i128 = 693_147_180_559_945_309_417_232_121_458_176_568;
+const LN_10: i128 = 2_302_585_092_994_045_684_017_991_454_684_364_208;
+// Pi / 2 is split into two parts for exact range reduction of large arguments,
+// the low part contains the next 36 digits.
+const HALF_PI: i128 = 1_570_796_326_794_896_619_231_321_691_639_751_442;
+const HALF_PI_LOW: i128 = 98_584_699_687_552_910_487_472_296_153_908_203;
+
+// `nth_root` estimates roots with 56 binary digits after the point and checks them exactly
+// if they're closer than 2^-24 to a rounding boundary, the error is below 2^-40.
+const ROOT_FRACTION_BITS: u32 = 56;
+const ROOT_MARGIN: i128 = 1 << 32;
+// 16384 bits are enough to raise any root to the power of 256.
+type RootUint = Uint<256>;
+
+impl<const P: u8> FixedPoint<P> {
+    /// Pi rounded to the nearest.
+    pub const PI: FixedPoint<P> = {
+        let unit = SCALE / Self::COEF as i128;
+        FixedPoint(((2 * HALF_PI + unit / 2) / unit) as i64)
+    };
+
+    /// Returns the square root.
+    ///
+    /// The result is correctly rounded in every `RoundMode`.
+    /// Fails with `DomainViolation` for negative values.
+    pub fn rsqrt(self, mode: RoundMode) -> Result<FixedPoint<P>, ArithmeticError> {
+        if self.0 < 0 {
+            return Err(ArithmeticError::DomainViolation);
+        }
+
+        // sqrt(m / COEF) * COEF = sqrt(m * COEF), the product fits because both are below 2^63.
+        let square = self.0 as u128 * Self::COEF as u128;
+        let root = iroot(square, 2);
+        let rest = square - root * root;
+
+        if rest == 0 {
+            return to_fixed_point(root);
+        }
+
+        // The exact root is above `root + 1/2` iff `square > root^2 + root + 1/4`,
+        // it's never equal because `square` is an integer.
+        let half = if rest > root {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+
+        to_fixed_point(round_up(root, half, mode)?)
+    }
+
+    /// Raises to an integer power using exponentiation by squaring.
+    ///
+    /// Every intermediate multiplication is rounded with `mode`, so the error grows with `exp`.
+    /// A negative `exp` takes the reciprocal of the base first, also rounded with `mode`.
+    /// Use `rpow_precise` to keep the error about 1 ulp.
+    ///
+    /// Fails with `Overflow` if an intermediate result overflows, like `rmul`,
+    /// and with `DivisionByZero` if zero is raised to a negative power.
+    /// Zero to the power of zero is one.
+    pub fn rpow(self, exp: i32, mode: RoundMode) -> Result<FixedPoint<P>, ArithmeticError> {
+        let mut base = if exp < 0 { self.recip(mode)? } else { self };
+        let mut exp = exp.unsigned_abs();
+        let mut result = Self::ONE;
+
+        while exp > 0 {
+            if exp % 2 == 1 {
+                result = result.rmul(base, mode)?;
+            }
+
+            exp /= 2;
+
+            if exp > 0 {
+                base = base.rmul(base, mode)?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// The same as `rpow`, but intermediate results keep 18 guard digits in `i128`
+    /// and are rounded half to even. Only the final result is rounded with `mode`,
+    /// so it's approximately within 1 ulp of the exact value. Intermediate errors grow
+    /// with `|exp|`, so the bound isn't guaranteed for huge exponents.
+    pub fn rpow_precise(self, exp: i32, mode: RoundMode) -> Result<FixedPoint<P>, ArithmeticError> {
+        let guard = 10i128.pow(GUARD_DIGITS);
+        let one = i128::from(Self::COEF) * guard;
+
+        let base = i128::from(self.0) * guard;
+        let mut base = if exp < 0 {
+            mul_div_128(one, one, base, RoundMode::HalfEven)?
+        } else {
+            base
+        };
+        let mut exp = exp.unsigned_abs();
+        let mut result = one;
+
+        while exp > 0 {
+            if exp % 2 == 1 {
+                result = mul_div_128(result, base, one, RoundMode::HalfEven)?;
+            }
+
+            exp /= 2;
+
+            if exp > 0 {
+                base = mul_div_128(base, base, one, RoundMode::HalfEven)?;
+            }
+        }
+
+        to_fixed_point(mul_div_128(result, 1, guard, mode)?)
+    }
+
+    /// Raises to a fractional power, computed as `e^(exp * ln(self))`.
+    ///
+    /// Integer exponents that fit `i32` are delegated to `rpow_precise`. Otherwise, the result
+    /// is within 1 ulp of the exact value, see `ln` for error bounds. Exact results are detected
+    /// if both sides of `result^q = self^p` for `exp = p / q` are representable, e.g. `4^0.5`.
+    ///
+    /// Fails with `DomainViolation` if a negative value is raised to a non-integer power,
+    /// with `DivisionByZero` if zero is raised to a negative power and with `Overflow` like `rmul`.
+    pub fn rpowf(
+        self,
+        exp: FixedPoint<P>,
+        mode: RoundMode,
+    ) -> Result<FixedPoint<P>, ArithmeticError> {
+        let (int, frac) = (exp.0 / Self::COEF, exp.0 % Self::COEF);
+
+        if frac == 0 {
+            if let Ok(int) = i32::try_from(int) {
+                return self.rpow_precise(int, mode);
+            }
+        }
+
+        if self.0 < 0 && frac != 0 {
+            return Err(ArithmeticError::DomainViolation);
+        }
+
+        if self.0 == 0 {
+            return if exp.0 > 0 {
+                Ok(Self::ZERO)
+            } else {
+                Err(ArithmeticError::DivisionByZero)
+            };
+        }
+
+        // Only integer powers of negative values get here, the sign depends on the parity.
+        let negative = self.0 < 0 && int % 2 != 0;
+        let magnitude = self.0.unsigned_abs();
+
+        if magnitude == Self::COEF.unsigned_abs() {
+            return Ok(if negative { Self::MINUS_ONE } else { Self::ONE });
+        }
+
+        // y = exp * ln(self) with `SCALE`, values beyond the bounds below saturate.
+        let ln = Self::ln_scaled(magnitude);
+        let y = mul_div_128(
+            ln,
+            i128::from(exp.0),
+            i128::from(Self::COEF),
+            RoundMode::TowardZero,
+        )
+        .unwrap_or(if (ln < 0) != (exp.0 < 0) {
+            i128::MIN
+        } else {
+            i128::MAX
+        });
+
+        // The same bounds as in `exp`.
+        if y > 44 * SCALE {
+            return Err(ArithmeticError::Overflow);
+        }
+
+        if y < -50 * SCALE {
+            let away = mode.is_away_from_zero(negative, Ordering::Less, false)?;
+            return Ok(FixedPoint(match (away, negative) {
+                (false, _) => 0,
+                (true, false) => 1,
+                (true, true) => -1,
+            }));
+        }
+
+        let (k, value) = exp_scaled(y);
+        let value = if negative { -value } else { value };
+
+        // Detect exact powers: for `exp = p / q` in lowest terms, `result^q = self^p`.
+        let divisor = gcd(exp.0.unsigned_abs(), Self::COEF.unsigned_abs());
+        let nearest = Self::unscale(value, k, RoundMode::HalfEven).and_then(to_fixed_point);
+        let exact = match (
+            &nearest,
+            i32::try_from(exp.0 / divisor as i64),
+            i32::try_from(Self::COEF.unsigned_abs() / divisor),
+        ) {
+            (Ok(nearest), Ok(p), Ok(q)) => nearest
+                .rpow(q, RoundMode::Unnecessary)
+                .is_ok_and(|power| self.rpow(p, RoundMode::Unnecessary) == Ok(power)),
+            _ => false,
+        };
+
+        if exact {
+            return nearest;
+        }
+
+        if matches!(mode, RoundMode::Unnecessary) {
+            return Err(ArithmeticError::Inexact);
+        }
+
+        to_fixed_point(Self::unscale(value, k, mode)?)
+    }
+
+    /// Returns the natural logarithm.
+    ///
+    /// The result is within 1 ulp of the exact value and is rounded correctly unless
+    /// the exact value is closer than 10^-30 to a rounding boundary.
+    /// Fails with `DomainViolation` for non-positive values.
+    pub fn ln(self, mode: RoundMode) -> Result<FixedPoint<P>, ArithmeticError> {
+        if self.0 <= 0 {
+            return Err(ArithmeticError::DomainViolation);
+        }
+
+        if self == Self::ONE {
+            return Ok(Self::ZERO);
+        }
+
+        from_scaled(Self::ln_scaled(self.0.unsigned_abs()), mode)
+    }
+
+    /// Returns the base 10 logarithm, see `ln` for error bounds.
+    ///
+    /// Exact for powers of ten.
+    pub fn log10(self, mode: RoundMode) -> Result<FixedPoint<P>, ArithmeticError> {
+        if self.0 <= 0 {
+            return Err(ArithmeticError::DomainViolation);
+        }
+
+        let digits = self.0.ilog10();
+
+        if 10i64.pow(digits) == self.0 {
+            return from_integer(i64::from(digits) - i64::from(P));
+        }
+
+        from_scaled(
+            mul_div_128(
+                Self::ln_scaled(self.0.unsigned_abs()),
+                SCALE,
+                LN_10,
+                RoundMode::TowardZero,
+            )?,
+            mode,
+        )
+    }
+
+    /// Returns the base 2 logarithm, see `ln` for error bounds.
+    ///
+    /// Exact for powers of two.
+    pub fn log2(self, mode: RoundMode) -> Result<FixedPoint<P>, ArithmeticError> {
+        if self.0 <= 0 {
+            return Err(ArithmeticError::DomainViolation);
+        }
+
+        let (int, frac) = (self.0 / Self::COEF, self.0 % Self::COEF);
+
+        if frac == 0 && int.unsigned_abs().is_power_of_two() {
+            return from_integer(i64::from(int.ilog2()));
+        }
+
+        if Self::COEF % self.0 == 0 && (Self::COEF / self.0).unsigned_abs().is_power_of_two() {
+            return from_integer(-i64::from((Self::COEF / self.0).ilog2()));
+        }
+
+        from_scaled(
+            mul_div_128(
+                Self::ln_scaled(self.0.unsigned_abs()),
+                SCALE,
+                LN_2,
+                RoundMode::TowardZero,
+            )?,
+            mode,
+        )
+    }
+
+    /// Returns `e^self`, see `ln` for error bounds.
+    pub fn exp(self, mode: RoundMode) -> Result<FixedPoint<P>, ArithmeticError> {
+        if self.0 == 0 {
+            return Ok(Self::ONE);
+        }
+
+        let x = i128::from(self.0);
+        let coef = i128::from(Self::COEF);
+
+        // e^44 doesn't fit any precision, e^-50 is below a half of 1 ulp of any precision.
+        if x > 44 * coef {
+            return Err(ArithmeticError::Overflow);
+        }
+
+        if x < -50 * coef {
+            let away = mode.is_away_from_zero(false, Ordering::Less, false)?;
+            return Ok(FixedPoint(i64::from(away)));
+        }
+
+        if matches!(mode, RoundMode::Unnecessary) {
+            return Err(ArithmeticError::Inexact);
+        }
+
+        let x = x * 10i128.pow(SCALE_DIGITS - u32::from(P));
+        let (k, value) = exp_scaled(x);
+        to_fixed_point(Self::unscale(value, k, mode)?)
+    }
+
+    /// Returns the `n`-th root.
+    ///
+    /// The result is correctly rounded in every `RoundMode`. If `mantissa * COEF^(n - 1) * 2^n`
+    /// fits `u128`, e.g. for square and cube roots at precision 9, the root is computed with
+    /// integer Newton's iteration. Otherwise, it's estimated as `e^(ln(self) / n)` and checked
+    /// by raising it to the `n`-th power in 16384-bit integers if it's close to a rounding boundary.
+    ///
+    /// Fails with `DomainViolation` for even roots of negative values and the zeroth root,
+    /// and with `Overflow` if such a check doesn't fit, that's possible only for `n > 256`.
+    pub fn nth_root(self, n: u32, mode: RoundMode) -> Result<FixedPoint<P>, ArithmeticError> {
+        let negative = self.0 < 0;
+
+        if n == 0 || negative && n % 2 == 0 {
+            return Err(ArithmeticError::DomainViolation);
+        }
+
+        let magnitude = self.0.unsigned_abs();
+
+        if n == 1 || self.0 == 0 || magnitude == Self::COEF.unsigned_abs() {
+            return Ok(self);
+        }
+
+        // root = (m / COEF)^(1/n) * COEF = (m * COEF^(n - 1))^(1/n), an extra binary digit
+        // is computed to round: (m * COEF^(n - 1) * 2^n)^(1/n) = 2 * root.
+        let radicand = u128::from(Self::COEF.unsigned_abs())
+            .checked_pow(n - 1)
+            .and_then(|power| power.checked_mul(u128::from(magnitude)))
+            .filter(|radicand| radicand.leading_zeros() >= n);
+
+        let (double, exact) = match radicand {
+            Some(radicand) => {
+                let radicand = radicand << n;
+                let double = iroot(radicand, n);
+                (double, double.checked_pow(n) == Some(radicand))
+            }
+            None => Self::double_root(magnitude, n)?,
+        };
+
+        let root = i128::try_from(double / 2).expect("impossible");
+        let root = if negative { -root } else { root };
+
+        // An exact double root is even, because the radicand is even.
+        if exact {
+            return to_fixed_point(root);
+        }
+
+        // The exact root is never a half, because the radicand is even.
+        let half = if double % 2 == 1 {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+
+        if mode.is_away_from_zero(negative, half, root % 2 != 0)? {
+            return to_fixed_point(root + if negative { -1 } else { 1 });
+        }
+
+        to_fixed_point(root)
+    }
+
+    /// Returns the sine of an angle in radians, see `ln` for error bounds.
+    ///
+    /// Exact only for zero.
+    pub fn sin(self, mode: RoundMode) -> Result<FixedPoint<P>, ArithmeticError> {
+        if self.0 == 0 {
+            return Ok(Self::ZERO);
+        }
+
+        let (quarter, r) = self.reduce_angle();
+        from_scaled(sin_scaled(quarter, r), mode)
+    }
+
+    /// Returns the cosine of an angle in radians, see `ln` for error bounds.
+    ///
+    /// Exact only for zero.
+    pub fn cos(self, mode: RoundMode) -> Result<FixedPoint<P>, ArithmeticError> {
+        if self.0 == 0 {
+            return Ok(Self::ONE);
+        }
+
+        // cos(x) = sin(x + pi/2).
+        let (quarter, r) = self.reduce_angle();
+        from_scaled(sin_scaled(quarter + 1, r), mode)
+    }
+
+    /// Returns the four-quadrant arctangent of `self` (y) and `x` in radians,
+    /// the result is in `[-PI, PI]`, see `ln` for error bounds.
+    ///
+    /// Exact only for zero `self` and positive `x`.
+    /// Fails with `DomainViolation` if both arguments are zero.
+    pub fn atan2(
+        self,
+        x: FixedPoint<P>,
+        mode: RoundMode,
+    ) -> Result<FixedPoint<P>, ArithmeticError> {
+        let (y, x) = (i128::from(self.0), i128::from(x.0));
+
+        if y == 0 && x == 0 {
+            return Err(ArithmeticError::DomainViolation);
+        }
+
+        if y == 0 && x > 0 {
+            return Ok(Self::ZERO);
+        }
+
+        let (a, b) = (y.abs(), x.abs());
+        let t = scaled_div(a.min(b), a.max(b));
+
+        // atan(t) = pi/4 + atan((t - 1) / (t + 1)), so the series argument is at most 1/2.
+        let mut angle = if t > SCALE / 2 {
+            HALF_PI / 2 + atan_series(scaled_div(t - SCALE, t + SCALE))
+        } else {
+            atan_series(t)
+        };
+
+        if a > b {
+            angle = HALF_PI - angle;
+        }
+
+        if x < 0 {
+            angle = 2 * HALF_PI - angle;
+        }
+
+        if y < 0 {
+            angle = -angle;
+        }
+
+        from_scaled(angle, mode)
+    }
+
+    /// Splits an angle into `quarter * pi/2 + r`, returning `quarter` and `r` with `SCALE`,
+    /// where `|r| <= pi/4` up to a rounding error.
+    fn reduce_angle(self) -> (i128, i128) {
+        let unit = SCALE / i128::from(Self::COEF);
+        let x = I256::from_i128(i128::from(self.0)).mul(I256::from_i128(unit));
+        let quarter =
+            div_256(x, I256::from_i128(HALF_PI), RoundMode::HalfEven).expect("impossible");
+
+        // r = x - quarter * (HALF_PI + HALF_PI_LOW / SCALE), `quarter` is below 2^63.
+        let quarter_256 = I256::from_i128(quarter);
+        let low = div_256(
+            quarter_256.mul(I256::from_i128(HALF_PI_LOW)),
+            I256::from_i128(SCALE),
+            RoundMode::HalfEven,
+        )
+        .expect("impossible");
+        let r = x
+            .sub(quarter_256.mul(I256::from_i128(HALF_PI)))
+            .to_i128()
+            .expect("impossible");
+
+        (quarter, r - low)
+    }
+
+    /// Returns the mantissa of `2^k * value` for `value` with `SCALE`.
+    fn unscale(value: i128, k: i128, mode: RoundMode) -> Result<i128, ArithmeticError> {
+        let numerator = I256::from_i128(value).mul(I256::from_i128(i128::from(Self::COEF)));
+        let denominator = I256::from_i128(SCALE);
+
+        if k >= 0 {
+            div_256(numerator.mul(I256::from_i128(1 << k)), denominator, mode)
+        } else {
+            div_256(numerator, denominator.mul(I256::from_i128(1 << -k)), mode)
+        }
+    }
+
+    /// Returns the floor of `2 * root` for `nth_root` and whether it's exact,
+    /// when the radicand doesn't fit `u128`.
+    fn double_root(magnitude: u64, n: u32) -> Result<(u128, bool), ArithmeticError> {
+        let ln = Self::ln_scaled(magnitude) / i128::from(n);
+        let (k, value) = exp_scaled(ln);
+
+        // `2 * root` with extra binary digits, it's below 2^64 for any precision.
+        // The error of the estimate is far below `ROOT_MARGIN` of them.
+        let unit = 1i128 << ROOT_FRACTION_BITS;
+        let estimate = Self::unscale(
+            value,
+            k + 1 + i128::from(ROOT_FRACTION_BITS),
+            RoundMode::Floor,
+        )?;
+        let fraction = estimate % unit;
+
+        if fraction > ROOT_MARGIN && fraction < unit - ROOT_MARGIN {
+            return Ok(((estimate / unit) as u128, false));
+        }
+
+        // The nearest integer is the exact `2 * root` or a rounding boundary close to it,
+        // compare its power with the radicand: (2 * root)^n = m * COEF^(n - 1) * 2^n.
+        let candidate = ((estimate + unit / 2) / unit) as u128;
+        let power = RootUint::from_u128(candidate).checked_pow(n);
+        let radicand = RootUint::from_u128(2 * Self::COEF.unsigned_abs() as u128)
+            .checked_pow(n - 1)
+            .and_then(|power| power.checked_mul(&RootUint::from_u128(2 * u128::from(magnitude))));
+
+        match (power, radicand) {
+            (Some(power), Some(radicand)) => Ok(match power.cmp(&radicand) {
+                Ordering::Less => (candidate, false),
+                Ordering::Equal => (candidate, true),
+                Ordering::Greater => (candidate - 1, false),
+            }),
+            _ => Err(ArithmeticError::Overflow),
+        }
+    }
+
+    /// Returns the natural logarithm of a positive mantissa with `SCALE`.
+    fn ln_scaled(mantissa: u64) -> i128 {
+        // ln(m / COEF) = k * ln(2) + ln(y) - P * ln(10), where m = 2^k * y, 1 <= y < 2.
+        let k = 63 - mantissa.leading_zeros();
+        let y = scaled_div(i128::from(mantissa), 1 << k);
+
+        // ln(y) = 2 * atanh(z), z = (y - 1) / (y + 1) < 1/3.
+        let z = scaled_div(y - SCALE, y + SCALE);
+        let z2 = scaled_mul(z, z);
+
+        let mut sum = z;
+        let mut term = z;
+        let mut n = 1;
+
+        while term != 0 {
+            term = scaled_mul(term, z2);
+            n += 2;
+            sum += term / n;
+        }
+
+        i128::from(k) * LN_2 + 2 * sum - i128::from(P) * LN_10
+    }
+}
+
+/// Splits `e^x` for `x` with `SCALE` into `2^k * e^r`, returning `k` and `e^r` with `SCALE`.
+fn exp_scaled(x: i128) -> (i128, i128) {
+    // 0 <= r < ln(2).
+    let k = x.div_euclid(LN_2);
+    let r = x - k * LN_2;
+
+    let mut sum = SCALE;
+    let mut term = SCALE;
+    let mut n = 0;
+
+    while term != 0 {
+        n += 1;
+        term = mul_div_128(term, r, SCALE * n, RoundMode::TowardZero).expect("impossible");
+        sum += term;
+    }
+
+    (k, sum)
+}
+
+/// Returns `sin(quarter * pi/2 + r)` for `r` with `SCALE`, `|r| <= pi/4`.
+fn sin_scaled(quarter: i128, r: i128) -> i128 {
+    let r2 = scaled_mul(r, r);
+
+    // Taylor series of sine (`n = 1`) and cosine (`n = 0`).
+    let series = |mut term: i128, mut n: i128| {
+        let mut sum = term;
+
+        while term != 0 {
+            term = -scaled_mul(term, r2) / ((n + 1) * (n + 2));
+            n += 2;
+            sum += term;
+        }
+
+        sum
+    };
+
+    match quarter.rem_euclid(4) {
+        0 => series(r, 1),
+        1 => series(SCALE, 0),
+        2 => -series(r, 1),
+        _ => -series(SCALE, 0),
+    }
+}
+
+/// Returns `atan(t)` for `t` with `SCALE`, `|t| <= 1/2`.
+fn atan_series(t: i128) -> i128 {
+    let t2 = scaled_mul(t, t);
+
+    let mut sum = t;
+    let mut term = t;
+    let mut n = 1;
+
+    while term != 0 {
+        term = -scaled_mul(term, t2);
+        n += 2;
+        sum += term / n;
+    }
+
+    sum
+}
+
+/// Floor of the `n`-th root, `n >= 2`, computed with Newton's iteration.
+fn iroot(value: u128, n: u32) -> u128 {
+    if value < 2 {
+        return value;
+    }
+
+    // Start above the root, the iteration decreases monotonically to the floor root.
+    let bits = 128 - value.leading_zeros();
+    let mut x = 1u128 << bits.div_ceil(n);
+    let n = u128::from(n);
+
+    loop {
+        // x' = ((n - 1) * x + value / x^(n - 1)) / n
+        let quotient = match x.checked_pow((n - 1) as u32) {
+            Some(power) => value / power,
+            None => 0,
+        };
+        let next = ((n - 1) * x + quotient) / n;
+
+        if next >= x {
+            return x;
+        }
+
+        x = next;
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+/// Multiplies values with `SCALE`, they must be small enough to not overflow.
+fn scaled_mul(a: i128, b: i128) -> i128 {
+    mul_div_128(a, b, SCALE, RoundMode::TowardZero).expect("impossible")
+}
+
+/// Divides values, returning the result with `SCALE`.
+fn scaled_div(a: i128, b: i128) -> i128 {
+    mul_div_128(a, SCALE, b, RoundMode::TowardZero).expect("impossible")
+}
+
+/// Rounds an irrational value with `SCALE` to the precision.
+fn from_scaled<const P: u8>(
+    value: i128,
+    mode: RoundMode,
+) -> Result<FixedPoint<P>, ArithmeticError> {
+    if matches!(mode, RoundMode::Unnecessary) {
+        return Err(ArithmeticError::Inexact);
+    }
+
+    let unit = 10i128.pow(SCALE_DIGITS - u32::from(P));
+    to_fixed_point(mul_div_128(value, 1, unit, mode)?)
+}
+
+fn from_integer<const P: u8>(value: i64) -> Result<FixedPoint<P>, ArithmeticError> {
+    value
+        .checked_mul(FixedPoint::<P>::COEF)
+        .map(FixedPoint)
+        .ok_or(ArithmeticError::Overflow)
+}
+
+/// Moves a truncated non-negative `result` up if required by `mode`.
+fn round_up(result: u128, half: Ordering, mode: RoundMode) -> Result<u128, ArithmeticError> {
+    let away = mode.is_away_from_zero(false, half, result % 2 == 1)?;
+    Ok(result + u128::from(away))
+}
+
+fn to_fixed_point<T, const P: u8>(mantissa: T) -> Result<FixedPoint<P>, ArithmeticError>
+where
+    i64: TryFrom<T>,
+{
+    i64::try_from(mantissa)
+        .map(FixedPoint)
+        .map_err(|_| ArithmeticError::Overflow)
+}
+
+fn narrow<const P: u8>(
+    value: Result<FixedPoint<P>, ArithmeticError>,
+) -> Result<FixedPoint32<P>, ArithmeticError> {
+    value.and_then(|value| FixedPoint32::try_from(value).map_err(|_| ArithmeticError::Overflow))
+}
+
+// Computing with the wider layout doesn't change rounding, because the precision is the same.
+impl<const P: u8> FixedPoint32<P> {
+    /// Returns the square root, see `FixedPoint::rsqrt`.
+    pub fn rsqrt(self, mode: RoundMode) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).rsqrt(mode))
+    }
+
+    /// Raises to an integer power, see `FixedPoint::rpow`.
+    pub fn rpow(self, exp: i32, mode: RoundMode) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).rpow(exp, mode))
+    }
+
+    /// Raises to an integer power, see `FixedPoint::rpow_precise`.
+    pub fn rpow_precise(
+        self,
+        exp: i32,
+        mode: RoundMode,
+    ) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).rpow_precise(exp, mode))
+    }
+
+    /// Raises to a fractional power, see `FixedPoint::rpowf`.
+    pub fn rpowf(
+        self,
+        exp: FixedPoint32<P>,
+        mode: RoundMode,
+    ) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).rpowf(FixedPoint::from(exp), mode))
+    }
+
+    /// Returns the natural logarithm, see `FixedPoint::ln`.
+    pub fn ln(self, mode: RoundMode) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).ln(mode))
+    }
+
+    /// Returns the base 10 logarithm, see `FixedPoint::log10`.
+    pub fn log10(self, mode: RoundMode) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).log10(mode))
+    }
+
+    /// Returns the base 2 logarithm, see `FixedPoint::log2`.
+    pub fn log2(self, mode: RoundMode) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).log2(mode))
+    }
+
+    /// Returns `e^self`, see `FixedPoint::exp`.
+    pub fn exp(self, mode: RoundMode) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).exp(mode))
+    }
+
+    /// Returns the `n`-th root, see `FixedPoint::nth_root`.
+    pub fn nth_root(self, n: u32, mode: RoundMode) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).nth_root(n, mode))
+    }
+
+    /// Returns the sine, see `FixedPoint::sin`.
+    pub fn sin(self, mode: RoundMode) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).sin(mode))
+    }
+
+    /// Returns the cosine, see `FixedPoint::cos`.
+    pub fn cos(self, mode: RoundMode) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).cos(mode))
+    }
+
+    /// Returns the four-quadrant arctangent, see `FixedPoint::atan2`.
+    pub fn atan2(
+        self,
+        x: FixedPoint32<P>,
+        mode: RoundMode,
+    ) -> Result<FixedPoint32<P>, ArithmeticError> {
+        narrow(FixedPoint::from(self).atan2(FixedPoint::from(x), mode))
+    }
+}
@@ -0,0 +1,158 @@
+//! Rounding `a * b / c` without intermediate overflow, shared by `rmul` and `rdiv`.
+//!
+//! Functions are `const fn` to be usable in const arithmetic.
+
+use core::cmp::Ordering;
+
+use crate::i256::I256;
+use crate::ops::RoundMode;
+use crate::ArithmeticError;
+
+macro_rules! impl_mul_div {
+    ($fn:ident, $wrapping_fn:ident, $wide_fn:ident, $layout:ident, $promotion:ident) => {
+        #[inline]
+        pub(crate) const fn $fn(
+            a: $layout,
+            b: $layout,
+            c: $layout,
+            mode: RoundMode,
+        ) -> Result<$layout, ArithmeticError> {
+            let result = match $wide_fn(a, b, c, mode) {
+                Ok(result) => result,
+                Err(err) => return Err(err),
+            };
+
+            if result < $layout::MIN as $promotion || result > $layout::MAX as $promotion {
+                return Err(ArithmeticError::Overflow);
+            }
+
+            Ok(result as $layout)
+        }
+
+        /// The same as the checked version, but the rounded result wraps around on overflow.
+        #[inline]
+        pub(crate) const fn $wrapping_fn(
+            a: $layout,
+            b: $layout,
+            c: $layout,
+            mode: RoundMode,
+        ) -> Result<$layout, ArithmeticError> {
+            match $wide_fn(a, b, c, mode) {
+                Ok(result) => Ok(result as $layout),
+                Err(err) => Err(err),
+            }
+        }
+
+        // TODO(loyd): avoid promoted arithmetic when possible,
+        //      because LLVM doesn't replace 128bit division by const with multiplication.
+        #[inline]
+        const fn $wide_fn(
+            a: $layout,
+            b: $layout,
+            c: $layout,
+            mode: RoundMode,
+        ) -> Result<$promotion, ArithmeticError> {
+            if c == 0 {
+                return Err(ArithmeticError::DivisionByZero);
+            }
+
+            let numerator = a as $promotion * b as $promotion;
+            let denominator = c as $promotion;
+            let (mut result, loss) = (numerator / denominator, numerator % denominator);
+
+            if loss != 0 {
+                let negative = (numerator < 0) != (denominator < 0);
+                let loss = loss.unsigned_abs();
+                let rest = denominator.unsigned_abs() - loss;
+                let half = if loss < rest {
+                    Ordering::Less
+                } else if loss > rest {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                };
+
+                match mode.is_away_from_zero(negative, half, result % 2 != 0) {
+                    Ok(true) => result += if negative { -1 } else { 1 },
+                    Ok(false) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+
+            Ok(result)
+        }
+    };
+}
+
+impl_mul_div!(mul_div_32, wrapping_mul_div_32, wide_mul_div_32, i32, i64);
+impl_mul_div!(mul_div_64, wrapping_mul_div_64, wide_mul_div_64, i64, i128);
+
+#[inline]
+pub(crate) const fn mul_div_128(
+    a: i128,
+    b: i128,
+    c: i128,
+    mode: RoundMode,
+) -> Result<i128, ArithmeticError> {
+    let numerator = I256::from_i128(a).mul(I256::from_i128(b));
+    div_256(numerator, I256::from_i128(c), mode)
+}
+
+/// The same as `mul_div_128`, but the rounded result wraps around on overflow.
+#[inline]
+pub(crate) const fn wrapping_mul_div_128(
+    a: i128,
+    b: i128,
+    c: i128,
+    mode: RoundMode,
+) -> Result<i128, ArithmeticError> {
+    let numerator = I256::from_i128(a).mul(I256::from_i128(b));
+
+    match wide_div_256(numerator, I256::from_i128(c), mode) {
+        Ok(result) => Ok(result.wrapping_to_i128()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Rounding division of 256-bit values, the result must fit `i128`.
+pub(crate) const fn div_256(
+    numerator: I256,
+    denominator: I256,
+    mode: RoundMode,
+) -> Result<i128, ArithmeticError> {
+    let result = match wide_div_256(numerator, denominator, mode) {
+        Ok(result) => result,
+        Err(err) => return Err(err),
+    };
+
+    match result.to_i128() {
+        Some(result) => Ok(result),
+        None => Err(ArithmeticError::Overflow),
+    }
+}
+
+const fn wide_div_256(
+    numerator: I256,
+    denominator: I256,
+    mode: RoundMode,
+) -> Result<I256, ArithmeticError> {
+    if denominator.is_zero() {
+        return Err(ArithmeticError::DivisionByZero);
+    }
+
+    let (mut result, loss) = numerator.divrem(denominator);
+
+    if !loss.is_zero() {
+        let negative = numerator.is_negative() != denominator.is_negative();
+        let loss = loss.abs();
+        let half = loss.compare(denominator.abs().sub(loss));
+
+        match mode.is_away_from_zero(negative, half, result.is_odd()) {
+            Ok(true) => result = result.add(I256::from_i128(if negative { -1 } else { 1 })),
+            Ok(false) => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(result)
+}
@@ -1,3 +1,7 @@
+use core::cmp::Ordering;
+
+use crate::ArithmeticError;
+
 pub trait Numeric: Copy {
     const ZERO: Self;
     const ONE: Self;
@@ -35,8 +39,85 @@ pub trait CheckedDiv<Rhs = Self> {
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RoundMode {
-    Ceil = 1,
-    Floor = -1,
+    /// Towards positive infinity.
+    Ceil,
+    /// Towards negative infinity.
+    Floor,
+    /// To the nearest, ties away from zero.
+    HalfUp,
+    /// To the nearest, ties towards zero.
+    HalfDown,
+    /// To the nearest, ties to the even neighbour (banker's rounding).
+    HalfEven,
+    /// Towards zero, i.e. truncation.
+    TowardZero,
+    /// Away from zero.
+    AwayFromZero,
+    /// Asserts that the result is exact, otherwise `ArithmeticError::Inexact` is returned.
+    Unnecessary,
+}
+
+impl RoundMode {
+    /// Decides whether an inexact result truncated towards zero must be moved away from zero.
+    ///
+    /// `half` is the discarded part compared to a half of the last digit,
+    /// `odd` is whether the truncated result is odd.
+    pub(crate) const fn is_away_from_zero(
+        self,
+        negative: bool,
+        half: Ordering,
+        odd: bool,
+    ) -> Result<bool, ArithmeticError> {
+        Ok(match self {
+            RoundMode::Ceil => !negative,
+            RoundMode::Floor => negative,
+            RoundMode::HalfUp => !matches!(half, Ordering::Less),
+            RoundMode::HalfDown => matches!(half, Ordering::Greater),
+            RoundMode::HalfEven => match half {
+                Ordering::Less => false,
+                Ordering::Equal => odd,
+                Ordering::Greater => true,
+            },
+            RoundMode::TowardZero => false,
+            RoundMode::AwayFromZero => true,
+            RoundMode::Unnecessary => return Err(ArithmeticError::Inexact),
+        })
+    }
+
+    /// Rounds `value` to an integer.
+    ///
+    /// Implemented without `f64::round` and friends, which aren't available in `core`.
+    pub(crate) fn round_f64(self, value: f64) -> Result<f64, ArithmeticError> {
+        // Values out of ±2^52 have no fractional part, NaN is also returned as is.
+        const INTEGER_THRESHOLD: f64 = 4_503_599_627_370_496.0;
+
+        if !(-INTEGER_THRESHOLD < value && value < INTEGER_THRESHOLD) {
+            return Ok(value);
+        }
+
+        let trunc = value as i64;
+        let fractional = value - trunc as f64;
+
+        if fractional == 0.0 {
+            return Ok(value);
+        }
+
+        let negative = fractional < 0.0;
+        let loss = if negative { -fractional } else { fractional };
+        let half = if loss < 0.5 {
+            Ordering::Less
+        } else if loss > 0.5 {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        };
+
+        Ok(if self.is_away_from_zero(negative, half, trunc % 2 != 0)? {
+            trunc as f64 + if negative { -1.0 } else { 1.0 }
+        } else {
+            trunc as f64
+        })
+    }
 }
 
 pub trait RoundingMul<Rhs = Self> {
@@ -52,3 +133,72 @@ pub trait RoundingDiv<Rhs = Self> {
 
     fn rdiv(self, rhs: Rhs, mode: RoundMode) -> Result<Self::Output, Self::Error>;
 }
+
+/// Addition that clamps the result at `MIN`/`MAX` instead of overflowing.
+pub trait SaturatingAdd<Rhs = Self> {
+    type Output;
+
+    fn saturating_add(self, rhs: Rhs) -> Self::Output;
+}
+
+/// Subtraction that clamps the result at `MIN`/`MAX` instead of overflowing.
+pub trait SaturatingSub<Rhs = Self> {
+    type Output;
+
+    fn saturating_sub(self, rhs: Rhs) -> Self::Output;
+}
+
+/// Rounding multiplication that clamps the result at `MIN`/`MAX` instead of overflowing.
+///
+/// Errors other than overflow are still reported.
+pub trait SaturatingMul<Rhs = Self> {
+    type Output;
+    type Error;
+
+    fn saturating_rmul(self, rhs: Rhs, mode: RoundMode) -> Result<Self::Output, Self::Error>;
+}
+
+/// Rounding division that clamps the result at `MIN`/`MAX` instead of overflowing.
+///
+/// Errors other than overflow (e.g. division by zero) are still reported.
+pub trait SaturatingDiv<Rhs = Self> {
+    type Output;
+    type Error;
+
+    fn saturating_rdiv(self, rhs: Rhs, mode: RoundMode) -> Result<Self::Output, Self::Error>;
+}
+
+/// Addition that wraps around at the boundary of the type instead of overflowing.
+pub trait WrappingAdd<Rhs = Self> {
+    type Output;
+
+    fn wrapping_add(self, rhs: Rhs) -> Self::Output;
+}
+
+/// Subtraction that wraps around at the boundary of the type instead of overflowing.
+pub trait WrappingSub<Rhs = Self> {
+    type Output;
+
+    fn wrapping_sub(self, rhs: Rhs) -> Self::Output;
+}
+
+/// Rounding multiplication that wraps around at the boundary of the type instead of overflowing.
+///
+/// The exact product is rounded first, then wrapped. Errors other than overflow are still reported.
+pub trait WrappingMul<Rhs = Self> {
+    type Output;
+    type Error;
+
+    fn wrapping_rmul(self, rhs: Rhs, mode: RoundMode) -> Result<Self::Output, Self::Error>;
+}
+
+/// Rounding division that wraps around at the boundary of the type instead of overflowing.
+///
+/// The exact quotient is rounded first, then wrapped.
+/// Errors other than overflow (e.g. division by zero) are still reported.
+pub trait WrappingDiv<Rhs = Self> {
+    type Output;
+    type Error;
+
+    fn wrapping_rdiv(self, rhs: Rhs, mode: RoundMode) -> Result<Self::Output, Self::Error>;
+}
@@ -0,0 +1,484 @@
+//! Parsing of decimal strings.
+//!
+//! `FromStr` and other parsing methods share the same core and differ only by `ParseOptions`.
+//!
+//! ```
+//! use fixnum::parse::ParseOptions;
+//! use fixnum::FixedPoint;
+//!
+//! let lenient = ParseOptions::lenient();
+//! assert_eq!(FixedPoint::<9>::parse_with("+1_000.25", lenient)?, "1000.25".parse()?);
+//! assert_eq!(FixedPoint::<9>::parse_with("12.5%", lenient)?, "0.125".parse()?);
+//!
+//! let strict = ParseOptions::strict();
+//! assert!(FixedPoint::<9>::parse_with(" 1.5", strict).is_err());
+//! # Ok::<(), fixnum::ParseFixedPointError>(())
+//! ```
+
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+
+use crate::locale::Locale;
+use crate::ops::RoundMode;
+use crate::{ArithmeticError, ParseFixedPointError};
+
+/// Unicode minus sign, `U+2212`.
+const UNICODE_MINUS: &[u8] = "\u{2212}".as_bytes();
+
+/// Describes which notations are accepted by parsing methods, see `FixedPoint::parse_with`.
+///
+/// Options are set by chaining methods on one of profiles:
+///
+/// ```
+/// use fixnum::ops::RoundMode;
+/// use fixnum::parse::ParseOptions;
+///
+/// let options = ParseOptions::strict()
+///     .underscores(true)
+///     .rounding(RoundMode::HalfEven);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParseOptions {
+    pub(crate) whitespace: bool,
+    pub(crate) plus_sign: bool,
+    pub(crate) unicode_minus: bool,
+    pub(crate) leading_point: bool,
+    pub(crate) trailing_point: bool,
+    pub(crate) leading_zeros: bool,
+    pub(crate) negative_zero: bool,
+    pub(crate) underscores: bool,
+    pub(crate) exponent: bool,
+    pub(crate) percent: bool,
+    pub(crate) rounding: RoundMode,
+    pub(crate) locale: Locale,
+}
+
+impl ParseOptions {
+    /// Options used by `FromStr`: `[+-]int[.frac][(e|E)[+-]exp]` surrounded by whitespace.
+    pub const fn new() -> ParseOptions {
+        ParseOptions {
+            whitespace: true,
+            plus_sign: true,
+            leading_zeros: true,
+            negative_zero: true,
+            exponent: true,
+            ..Self::strict()
+        }
+    }
+
+    /// Accepts only the canonical notation, i.e. `[-]int[.frac]` without whitespace,
+    /// redundant leading zeros and negative zero. Trailing zeros of `frac` are allowed.
+    pub const fn strict() -> ParseOptions {
+        ParseOptions {
+            whitespace: false,
+            plus_sign: false,
+            unicode_minus: false,
+            leading_point: false,
+            trailing_point: false,
+            leading_zeros: false,
+            negative_zero: false,
+            underscores: false,
+            exponent: false,
+            percent: false,
+            rounding: RoundMode::Unnecessary,
+            locale: Locale::new(),
+        }
+    }
+
+    /// Accepts every supported notation, e.g. `+1.5`, `.5`, `5.`, `007`, `-0`, `1_000.25`,
+    /// `−1` (Unicode minus), `12.5%` and `1.5e-3`, surrounded by whitespace.
+    pub const fn lenient() -> ParseOptions {
+        ParseOptions {
+            whitespace: true,
+            plus_sign: true,
+            unicode_minus: true,
+            leading_point: true,
+            trailing_point: true,
+            leading_zeros: true,
+            negative_zero: true,
+            underscores: true,
+            exponent: true,
+            percent: true,
+            rounding: RoundMode::Unnecessary,
+            locale: Locale::new(),
+        }
+    }
+
+    /// Skip leading and trailing whitespace.
+    pub const fn whitespace(mut self, enabled: bool) -> ParseOptions {
+        self.whitespace = enabled;
+        self
+    }
+
+    /// Accept an explicit `+` sign.
+    pub const fn plus_sign(mut self, enabled: bool) -> ParseOptions {
+        self.plus_sign = enabled;
+        self
+    }
+
+    /// Accept the Unicode minus sign `−` (`U+2212`) along with `-`.
+    pub const fn unicode_minus(mut self, enabled: bool) -> ParseOptions {
+        self.unicode_minus = enabled;
+        self
+    }
+
+    /// Accept numbers without the integral part, e.g. `.5`.
+    pub const fn leading_point(mut self, enabled: bool) -> ParseOptions {
+        self.leading_point = enabled;
+        self
+    }
+
+    /// Accept numbers without the fractional part after the point, e.g. `5.`.
+    pub const fn trailing_point(mut self, enabled: bool) -> ParseOptions {
+        self.trailing_point = enabled;
+        self
+    }
+
+    /// Accept redundant leading zeros, e.g. `007.5`.
+    pub const fn leading_zeros(mut self, enabled: bool) -> ParseOptions {
+        self.leading_zeros = enabled;
+        self
+    }
+
+    /// Accept negative zero, e.g. `-0` or `-0.00`.
+    pub const fn negative_zero(mut self, enabled: bool) -> ParseOptions {
+        self.negative_zero = enabled;
+        self
+    }
+
+    /// Accept `_` between digits, e.g. `1_000.25`.
+    pub const fn underscores(mut self, enabled: bool) -> ParseOptions {
+        self.underscores = enabled;
+        self
+    }
+
+    /// Accept an exponent, e.g. `1.5e-3` or `2E+4`.
+    pub const fn exponent(mut self, enabled: bool) -> ParseOptions {
+        self.exponent = enabled;
+        self
+    }
+
+    /// Accept a trailing `%`, which divides the value by 100.
+    pub const fn percent(mut self, enabled: bool) -> ParseOptions {
+        self.percent = enabled;
+        self
+    }
+
+    /// Round fractional digits beyond the precision with `mode`.
+    /// `RoundMode::Unnecessary`, the default, rejects them.
+    pub const fn rounding(mut self, mode: RoundMode) -> ParseOptions {
+        self.rounding = mode;
+        self
+    }
+
+    /// Use separators and the minus sign of `locale` instead of `.` and `-`.
+    pub const fn locale(mut self, locale: Locale) -> ParseOptions {
+        self.locale = locale;
+        self
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions::new()
+    }
+}
+
+/// Parses a decimal number, returning its mantissa for the given precision.
+///
+/// The exponent is applied exactly, excess digits are rounded according to `options`.
+/// The result must be narrowed to the target layout by the caller.
+pub(crate) fn fixed_point_from_str(
+    str: &str,
+    precision: u8,
+    options: ParseOptions,
+) -> Result<i128, ParseFixedPointError> {
+    // Positions are kept relative to the original string.
+    let (bytes, mut i) = if options.whitespace {
+        let start = str.len() - str.trim_start().len();
+        (str.trim_end().as_bytes(), start)
+    } else {
+        (str.as_bytes(), 0)
+    };
+
+    if i >= bytes.len() {
+        return Err(ParseFixedPointError::Empty);
+    }
+
+    let locale = &options.locale;
+    let (mut minus, mut point, mut group) = ([0; 4], [0; 4], [0; 4]);
+    let minus = locale.minus_sign.encode_utf8(&mut minus).as_bytes();
+    let point = locale.decimal_separator.encode_utf8(&mut point).as_bytes();
+    let group = match locale.grouping_separator {
+        Some(separator) => separator.encode_utf8(&mut group).as_bytes(),
+        None => &[],
+    };
+    let underscore: &[u8] = if options.underscores { b"_" } else { &[] };
+
+    let sign_start = i;
+    let mut negative = false;
+
+    if bytes[i..].starts_with(minus) {
+        negative = true;
+        i += minus.len();
+    } else if options.unicode_minus && bytes[i..].starts_with(UNICODE_MINUS) {
+        negative = true;
+        i += UNICODE_MINUS.len();
+    } else if options.plus_sign && bytes[i] == b'+' {
+        i += 1;
+    }
+
+    let integral_start = i;
+    let integral = scan_digits(bytes, &mut i, &[group, underscore]);
+    let mut fractional = &[][..];
+
+    if !group.is_empty() {
+        check_grouping(integral, integral_start, group, locale)?;
+    }
+
+    if !options.leading_zeros && integral.len() > 1 && integral[0] == b'0' {
+        return Err(ParseFixedPointError::InvalidDigit {
+            position: integral_start,
+        });
+    }
+
+    if bytes[i..].starts_with(point) && (!integral.is_empty() || options.leading_point) {
+        i += point.len();
+        fractional = scan_digits(bytes, &mut i, &[underscore]);
+
+        if fractional.is_empty() && !integral.is_empty() && !options.trailing_point {
+            return Err(ParseFixedPointError::InvalidDigit { position: i });
+        }
+    }
+
+    if integral.is_empty() && fractional.is_empty() {
+        let rest = &bytes[i..];
+        let missing = rest.is_empty() || rest.starts_with(point) || matches!(rest[0], b'e' | b'E');
+
+        return Err(if missing {
+            ParseFixedPointError::MissingIntegralPart
+        } else {
+            ParseFixedPointError::InvalidDigit { position: i }
+        });
+    }
+
+    let is_zero = digits_of(integral)
+        .chain(digits_of(fractional))
+        .all(|digit| digit == 0);
+
+    if negative && is_zero && !options.negative_zero {
+        return Err(ParseFixedPointError::InvalidDigit {
+            position: sign_start,
+        });
+    }
+
+    let mut exponent = 0i64;
+
+    if let (true, Some(b'e' | b'E')) = (options.exponent, bytes.get(i)) {
+        i += 1;
+
+        let negative = bytes.get(i) == Some(&b'-');
+
+        if negative || bytes.get(i) == Some(&b'+') {
+            i += 1;
+        }
+
+        let digits = scan_digits(bytes, &mut i, &[underscore]);
+
+        if digits.is_empty() {
+            return Err(ParseFixedPointError::InvalidDigit { position: i });
+        }
+
+        // Huge exponents saturate, such values overflow or need too many decimals anyway.
+        exponent = digits_of(digits).fold(0i64, |exponent, digit| {
+            exponent.saturating_mul(10).saturating_add(i64::from(digit))
+        });
+
+        if negative {
+            exponent = -exponent;
+        }
+    }
+
+    if options.percent && bytes.get(i) == Some(&b'%') {
+        i += 1;
+        exponent = exponent.saturating_sub(2);
+    }
+
+    if i < bytes.len() {
+        return Err(ParseFixedPointError::InvalidDigit { position: i });
+    }
+
+    let mode = options.rounding;
+    decimal_to_mantissa(negative, integral, fractional, exponent, precision, mode).map_err(|err| {
+        match err {
+            ArithmeticError::Inexact => ParseFixedPointError::ExcessPrecision {
+                digits: required_decimals(integral, fractional, exponent),
+            },
+            _ => ParseFixedPointError::Overflow,
+        }
+    })
+}
+
+/// Returns the mantissa of `±integral.fractional * 10^exponent` for the given precision,
+/// excess digits are rounded with `mode`.
+///
+/// Both parts must contain only ASCII digits and separators, which are skipped.
+pub(crate) fn decimal_to_mantissa(
+    negative: bool,
+    integral: &[u8],
+    fractional: &[u8],
+    exponent: i64,
+    precision: u8,
+    mode: RoundMode,
+) -> Result<i128, ArithmeticError> {
+    let decimals = digits_of(fractional).count();
+    let len = digits_of(integral).count() + decimals;
+    let scale = i64::try_from(decimals)
+        .ok()
+        .and_then(|decimals| exponent.checked_sub(decimals))
+        .map_or(i64::MIN, |exponent| {
+            exponent.saturating_add(i64::from(precision))
+        });
+    let excess = usize::try_from(scale.saturating_neg()).unwrap_or(0);
+
+    let mut digits = digits_of(integral).chain(digits_of(fractional));
+    let mut magnitude = 0u128;
+
+    for digit in digits.by_ref().take(len.saturating_sub(excess)) {
+        magnitude = magnitude
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(u128::from(digit)))
+            .ok_or(ArithmeticError::Overflow)?;
+    }
+
+    if scale > 0 && magnitude != 0 {
+        magnitude = u32::try_from(scale)
+            .ok()
+            .and_then(|scale| 10u128.checked_pow(scale))
+            .and_then(|multiplier| magnitude.checked_mul(multiplier))
+            .ok_or(ArithmeticError::Overflow)?;
+    }
+
+    // Compare the dropped digits with a half of the last kept one.
+    let half = if excess > len {
+        // Leading zeros are implied.
+        digits.any(|digit| digit != 0).then_some(Ordering::Less)
+    } else {
+        match digits.next() {
+            Some(first) => match (first.cmp(&5), digits.any(|digit| digit != 0)) {
+                (Ordering::Equal, true) => Some(Ordering::Greater),
+                (Ordering::Less, false) if first == 0 => None,
+                (half, _) => Some(half),
+            },
+            None => None,
+        }
+    };
+
+    if let Some(half) = half {
+        if mode.is_away_from_zero(negative, half, magnitude % 2 == 1)? {
+            magnitude = magnitude.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+        }
+    }
+
+    if !negative {
+        i128::try_from(magnitude).map_err(|_| ArithmeticError::Overflow)
+    } else if magnitude <= i128::MIN.unsigned_abs() {
+        Ok(0i128.wrapping_sub(magnitude as i128))
+    } else {
+        Err(ArithmeticError::Overflow)
+    }
+}
+
+/// Returns leading digits starting at `i` and moves `i` past them.
+///
+/// A separator is a part of digits only if surrounded by digits, empty ones are disabled.
+fn scan_digits<'a>(bytes: &'a [u8], i: &mut usize, separators: &[&[u8]]) -> &'a [u8] {
+    let start = *i;
+
+    while let Some(byte) = bytes.get(*i) {
+        if byte.is_ascii_digit() {
+            *i += 1;
+            continue;
+        }
+
+        let separator = separators.iter().find(|separator| {
+            !separator.is_empty()
+                && *i > start
+                && bytes[*i..].starts_with(separator)
+                && bytes
+                    .get(*i + separator.len())
+                    .is_some_and(u8::is_ascii_digit)
+        });
+
+        match separator {
+            Some(separator) => *i += separator.len(),
+            None => break,
+        }
+    }
+
+    &bytes[start..*i]
+}
+
+/// Checks that group separators in `integral` split it according to `locale`.
+///
+/// Separators are optional, but if any is present, all must be placed correctly.
+/// `offset` is the position of `integral` in the original string.
+fn check_grouping(
+    integral: &[u8],
+    offset: usize,
+    separator: &[u8],
+    locale: &Locale,
+) -> Result<(), ParseFixedPointError> {
+    let mut end = integral.len();
+    let mut digits = 0;
+    let mut group = 0;
+    let mut last_separator = None;
+
+    // Groups are counted from the right.
+    while end > 0 {
+        if integral[..end].ends_with(separator) {
+            end -= separator.len();
+
+            if digits != locale.group_size(group) {
+                return Err(ParseFixedPointError::InvalidDigit {
+                    position: offset + end,
+                });
+            }
+
+            last_separator = Some(offset + end);
+            digits = 0;
+            group += 1;
+        } else {
+            end -= 1;
+            digits += usize::from(integral[end].is_ascii_digit());
+        }
+    }
+
+    match last_separator {
+        Some(position) if digits > locale.group_size(group) => {
+            Err(ParseFixedPointError::InvalidDigit { position })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns values of digits, skipping separators.
+fn digits_of(bytes: &[u8]) -> impl DoubleEndedIterator<Item = u8> + '_ {
+    bytes
+        .iter()
+        .filter(|byte| byte.is_ascii_digit())
+        .map(|&byte| byte - b'0')
+}
+
+/// Returns the number of fractional digits of `integral.fractional * 10^exponent`
+/// without trailing zeros, saturating.
+fn required_decimals(integral: &[u8], fractional: &[u8], exponent: i64) -> usize {
+    let zeros = digits_of(integral)
+        .chain(digits_of(fractional))
+        .rev()
+        .take_while(|&digit| digit == 0)
+        .count();
+
+    let decimals = digits_of(fractional).count() as i64 - zeros as i64;
+    usize::try_from(decimals.saturating_sub(exponent)).unwrap_or(usize::MAX)
+}
@@ -0,0 +1,106 @@
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::ops::{Numeric, RoundMode, SaturatingAdd, SaturatingDiv, SaturatingMul, SaturatingSub};
+
+/// A wrapper that implements `std::ops` operators with saturating semantics.
+///
+/// All operations clamp the result at `MIN`/`MAX` instead of overflowing.
+/// Multiplication and division round using `Saturating::ROUND_MODE`.
+///
+/// # Panics
+///
+/// Division panics if the divisor is zero, like the division of primitive integers.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Saturating<T>(pub T);
+
+impl<T> Saturating<T> {
+    /// The rounding mode used by `*` and `/`.
+    pub const ROUND_MODE: RoundMode = RoundMode::HalfEven;
+}
+
+impl<T: fmt::Debug> fmt::Debug for Saturating<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Saturating<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: SaturatingAdd<Output = T>> Add for Saturating<T> {
+    type Output = Saturating<T>;
+
+    #[inline]
+    fn add(self, rhs: Saturating<T>) -> Saturating<T> {
+        Saturating(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl<T: SaturatingSub<Output = T>> Sub for Saturating<T> {
+    type Output = Saturating<T>;
+
+    #[inline]
+    fn sub(self, rhs: Saturating<T>) -> Saturating<T> {
+        Saturating(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl<T> Mul for Saturating<T>
+where
+    T: SaturatingMul<Output = T>,
+    T::Error: fmt::Debug,
+{
+    type Output = Saturating<T>;
+
+    #[inline]
+    fn mul(self, rhs: Saturating<T>) -> Saturating<T> {
+        let result = self.0.saturating_rmul(rhs.0, Self::ROUND_MODE);
+        Saturating(result.expect("saturating multiplication failed"))
+    }
+}
+
+impl<T> Div for Saturating<T>
+where
+    T: SaturatingDiv<Output = T>,
+    T::Error: fmt::Debug,
+{
+    type Output = Saturating<T>;
+
+    #[inline]
+    fn div(self, rhs: Saturating<T>) -> Saturating<T> {
+        let result = self.0.saturating_rdiv(rhs.0, Self::ROUND_MODE);
+        Saturating(result.expect("saturating division failed"))
+    }
+}
+
+impl<T: Numeric + SaturatingSub<Output = T>> Neg for Saturating<T> {
+    type Output = Saturating<T>;
+
+    #[inline]
+    fn neg(self) -> Saturating<T> {
+        Saturating(T::ZERO.saturating_sub(self.0))
+    }
+}
+
+macro_rules! impl_assign {
+    ($trait:ident, $method:ident, $op:ident, $op_method:ident) => {
+        impl<T: Copy> $trait for Saturating<T>
+        where
+            Saturating<T>: $op<Output = Saturating<T>>,
+        {
+            #[inline]
+            fn $method(&mut self, rhs: Saturating<T>) {
+                *self = $op::$op_method(*self, rhs);
+            }
+        }
+    };
+}
+
+impl_assign!(AddAssign, add_assign, Add, add);
+impl_assign!(SubAssign, sub_assign, Sub, sub);
+impl_assign!(MulAssign, mul_assign, Mul, mul);
+impl_assign!(DivAssign, div_assign, Div, div);
@@ -0,0 +1,250 @@
+//! Serde support with selectable representations.
+//!
+//! By default fixed-point numbers are serialized as strings, e.g. `"1.5"`.
+//! The `serde-float` feature makes floats the default, the `serde-repr` feature makes
+//! the raw mantissa the default (if both are enabled, `serde-repr` wins).
+//!
+//! A representation can be chosen for a specific field:
+//!
+//! ```
+//! use fixnum::FixedPoint;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Order {
+//!     #[serde(with = "fixnum::serde::str")]
+//!     price: FixedPoint<9>,
+//!     #[serde(with = "fixnum::serde::float")]
+//!     volume: FixedPoint<9>,
+//!     #[serde(with = "fixnum::serde::repr")]
+//!     fee: FixedPoint<9>,
+//! }
+//! ```
+//!
+//! Human-readable formats (e.g. JSON) accept strings, integers and floats regardless of
+//! the representation. Strings are validated by `FromStr`, floats are converted by
+//! `from_f64_shortest` with `RoundMode::HalfEven`, so `0.3` becomes exactly `0.3`.
+//! Integers mean whole numbers, except for `repr`, where they are mantissas.
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::marker::PhantomData;
+use core::primitive::str as Str;
+
+use ::serde::de::{self, Deserializer, Visitor};
+use ::serde::{Deserialize, Serialize, Serializer};
+
+use crate::ops::RoundMode;
+use crate::{FixedPoint, FixedPoint128, FixedPoint32};
+
+use self::private::FixedPointSerde;
+
+mod private {
+    use super::*;
+
+    use crate::{ArithmeticError, ParseFixedPointError};
+
+    pub trait FixedPointSerde:
+        Copy + fmt::Display + core::str::FromStr<Err = ParseFixedPointError>
+    {
+        fn to_f64(self) -> f64;
+        fn from_f64(value: f64) -> Result<Self, ArithmeticError>;
+        fn from_integer(value: i128) -> Option<Self>;
+        fn from_repr(value: i128) -> Option<Self>;
+        fn serialize_repr<S: Serializer>(self, serializer: S) -> Result<S::Ok, S::Error>;
+        fn deserialize_repr<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+    }
+}
+
+macro_rules! impl_serde {
+    ($name:ident, $layout:ident) => {
+        impl<const P: u8> FixedPointSerde for $name<P> {
+            fn to_f64(self) -> f64 {
+                $name::to_f64(self)
+            }
+
+            fn from_f64(value: f64) -> Result<Self, crate::ArithmeticError> {
+                $name::from_f64_shortest(value, RoundMode::HalfEven)
+            }
+
+            fn from_integer(value: i128) -> Option<Self> {
+                $layout::try_from(value)
+                    .ok()
+                    .and_then(|value| Self::try_from(value).ok())
+            }
+
+            fn from_repr(value: i128) -> Option<Self> {
+                $layout::try_from(value).ok().map($name::from_mantissa)
+            }
+
+            fn serialize_repr<S: Serializer>(self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.mantissa().serialize(serializer)
+            }
+
+            fn deserialize_repr<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                $layout::deserialize(deserializer).map($name::from_mantissa)
+            }
+        }
+
+        impl<const P: u8> Serialize for $name<P> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                default::serialize(self, serializer)
+            }
+        }
+
+        impl<'de, const P: u8> Deserialize<'de> for $name<P> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                default::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+impl_serde!(FixedPoint, i64);
+impl_serde!(FixedPoint128, i128);
+impl_serde!(FixedPoint32, i32);
+
+#[cfg(feature = "serde-repr")]
+use self::repr as default;
+
+#[cfg(all(feature = "serde-float", not(feature = "serde-repr")))]
+use self::float as default;
+
+#[cfg(not(any(feature = "serde-float", feature = "serde-repr")))]
+use self::str as default;
+
+struct FixedPointVisitor<T> {
+    integer_is_mantissa: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T> FixedPointVisitor<T> {
+    fn new(integer_is_mantissa: bool) -> Self {
+        Self {
+            integer_is_mantissa,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: FixedPointSerde> Visitor<'de> for FixedPointVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a fixed-point number as a string, an integer or a float")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &Str) -> Result<T, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<T, E> {
+        self.visit_i128(i128::from(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+        self.visit_i128(i128::from(value))
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<T, E> {
+        let result = if self.integer_is_mantissa {
+            T::from_repr(value)
+        } else {
+            T::from_integer(value)
+        };
+
+        result.ok_or_else(|| E::custom("overflow"))
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<T, E> {
+        let value = i128::try_from(value).map_err(|_| E::custom("overflow"))?;
+        self.visit_i128(value)
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<T, E> {
+        T::from_f64(value).map_err(E::custom)
+    }
+}
+
+/// Serializes to and deserializes from strings, e.g. `"1.5"`.
+pub mod str {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: FixedPointSerde,
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FixedPointSerde,
+        D: Deserializer<'de>,
+    {
+        let visitor = FixedPointVisitor::new(false);
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(visitor)
+        } else {
+            deserializer.deserialize_str(visitor)
+        }
+    }
+}
+
+/// Serializes to and deserializes from floats, e.g. `1.5`.
+///
+/// Note that floats can't represent all values exactly.
+pub mod float {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: FixedPointSerde,
+        S: Serializer,
+    {
+        serializer.serialize_f64(value.to_f64())
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FixedPointSerde,
+        D: Deserializer<'de>,
+    {
+        let visitor = FixedPointVisitor::new(false);
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(visitor)
+        } else {
+            deserializer.deserialize_f64(visitor)
+        }
+    }
+}
+
+/// Serializes to and deserializes from the raw mantissa, e.g. `1500000000` for `1.5`.
+pub mod repr {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: FixedPointSerde,
+        S: Serializer,
+    {
+        value.serialize_repr(serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FixedPointSerde,
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(FixedPointVisitor::new(true))
+        } else {
+            T::deserialize_repr(deserializer)
+        }
+    }
+}
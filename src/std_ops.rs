@@ -0,0 +1,97 @@
+//! Implementations of `std::ops` operators, enabled by the `std-ops` feature.
+//!
+//! Operators panic on overflow and division by zero. `*` and `/` round half to even.
+//! Use traits from `ops` to handle errors and choose rounding explicitly.
+
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::ops::{CheckedAdd, CheckedSub, RoundMode, RoundingDiv, RoundingMul};
+use crate::{ArithmeticError, FixedPoint, FixedPoint128, FixedPoint32};
+
+const ROUND_MODE: RoundMode = RoundMode::HalfEven;
+
+macro_rules! impl_std_ops {
+    ($name:ident) => {
+        impl<const P: u8> Add for $name<P> {
+            type Output = $name<P>;
+
+            #[inline]
+            fn add(self, rhs: $name<P>) -> $name<P> {
+                self.cadd(rhs).expect("attempt to add with overflow")
+            }
+        }
+
+        impl<const P: u8> Sub for $name<P> {
+            type Output = $name<P>;
+
+            #[inline]
+            fn sub(self, rhs: $name<P>) -> $name<P> {
+                self.csub(rhs).expect("attempt to subtract with overflow")
+            }
+        }
+
+        impl<const P: u8> Mul for $name<P> {
+            type Output = $name<P>;
+
+            #[inline]
+            fn mul(self, rhs: $name<P>) -> $name<P> {
+                self.rmul(rhs, ROUND_MODE)
+                    .expect("attempt to multiply with overflow")
+            }
+        }
+
+        impl<const P: u8> Div for $name<P> {
+            type Output = $name<P>;
+
+            #[inline]
+            fn div(self, rhs: $name<P>) -> $name<P> {
+                match self.rdiv(rhs, ROUND_MODE) {
+                    Ok(value) => value,
+                    Err(ArithmeticError::DivisionByZero) => panic!("attempt to divide by zero"),
+                    Err(_) => panic!("attempt to divide with overflow"),
+                }
+            }
+        }
+
+        impl<const P: u8> Neg for $name<P> {
+            type Output = $name<P>;
+
+            #[inline]
+            fn neg(self) -> $name<P> {
+                self.cneg().expect("attempt to negate with overflow")
+            }
+        }
+
+        impl<const P: u8> AddAssign for $name<P> {
+            #[inline]
+            fn add_assign(&mut self, rhs: $name<P>) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<const P: u8> SubAssign for $name<P> {
+            #[inline]
+            fn sub_assign(&mut self, rhs: $name<P>) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<const P: u8> MulAssign for $name<P> {
+            #[inline]
+            fn mul_assign(&mut self, rhs: $name<P>) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl<const P: u8> DivAssign for $name<P> {
+            #[inline]
+            fn div_assign(&mut self, rhs: $name<P>) {
+                *self = *self / rhs;
+            }
+        }
+    };
+}
+
+impl_std_ops!(FixedPoint);
+impl_std_ops!(FixedPoint128);
+impl_std_ops!(FixedPoint32);
@@ -1,14 +1,35 @@
 use anyhow::Result;
 
-use std::i64;
-
 use super::*;
 use crate::ops::RoundMode::Ceil;
 
+type FixedPoint = crate::FixedPoint<9>;
+
 fn fp(s: &str) -> Result<FixedPoint> {
     FixedPoint::from_str(s).map_err(From::from)
 }
 
+/// Checks an inexact result of `f` for `x` rounded with `Floor`, `HalfEven` and `Ceil`.
+fn check_rounding(
+    f: impl Fn(FixedPoint, RoundMode) -> Result<FixedPoint, ArithmeticError>,
+    x: &str,
+    expected: [&str; 3],
+) -> Result<()> {
+    let modes = [RoundMode::Floor, RoundMode::HalfEven, RoundMode::Ceil];
+
+    for (&mode, expected) in modes.iter().zip(&expected) {
+        assert_eq!(f(fp(x)?, mode)?, fp(expected)?, "{} {:?}", x, mode);
+    }
+
+    assert_eq!(
+        f(fp(x)?, RoundMode::Unnecessary),
+        Err(ArithmeticError::Inexact),
+        "{}",
+        x
+    );
+    Ok(())
+}
+
 #[test]
 fn from_decimal() -> Result<()> {
     let p1 = fp("5")?;
@@ -18,6 +39,95 @@ fn from_decimal() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn const_constructors() -> Result<()> {
+    const MANTISSA: FixedPoint = FixedPoint::from_mantissa(1_500_000_000);
+    const DECIMAL: Result<FixedPoint, FixedPointFromDecimalError> =
+        FixedPoint::from_decimal(15, -1);
+    const RAW: i64 = MANTISSA.mantissa();
+
+    assert_eq!(MANTISSA, fp("1.5")?);
+    assert_eq!(DECIMAL, Ok(MANTISSA));
+    assert_eq!(RAW, 1_500_000_000);
+
+    Ok(())
+}
+
+#[test]
+fn fixnum_macro() -> Result<()> {
+    const FEE: FixedPoint = fixnum!("0.0025");
+    static PRICE: crate::FixedPoint128<18> = fixnum!("-1.000000000000000001", FixedPoint128);
+
+    assert_eq!(FEE, fp("0.0025")?);
+    assert_eq!(PRICE, "-1.000000000000000001".parse()?);
+
+    let t = |value: FixedPoint, expected: &str| -> Result<()> {
+        assert_eq!(value, fp(expected)?);
+        Ok(())
+    };
+
+    t(fixnum!("0"), "0")?;
+    t(fixnum!("-0"), "0")?;
+    t(fixnum!("+1.02"), "1.02")?;
+    t(fixnum!("007.100"), "7.1")?;
+    t(fixnum!("123456789.123456789"), "123456789.123456789")?;
+    t(fixnum!("-9223372036.854775808"), "-9223372036.854775808")?;
+
+    let small: crate::FixedPoint32<9> = fixnum!("-2.147483648", FixedPoint32);
+    assert_eq!(small, crate::FixedPoint32::MIN);
+
+    Ok(())
+}
+
+#[test]
+fn const_arithmetic() -> Result<()> {
+    const BASE: FixedPoint = fixnum!("0.003");
+    const DOUBLE: FixedPoint = match BASE.const_mul(2) {
+        Ok(value) => value,
+        Err(_) => panic!("overflow"),
+    };
+    const THIRD: Result<FixedPoint, ArithmeticError> = BASE.const_rdiv_int(9, RoundMode::Floor);
+    const DISCOUNTED: Result<FixedPoint, ArithmeticError> =
+        BASE.const_rmul(fixnum!("0.85"), RoundMode::HalfEven);
+    const OVERFLOW: Result<FixedPoint, ArithmeticError> =
+        FixedPoint::MAX.const_add(FixedPoint::EPSILON);
+
+    assert_eq!(DOUBLE, fp("0.006")?);
+    assert_eq!(THIRD, Ok(fp("0.000333333")?));
+    assert_eq!(DISCOUNTED, Ok(fp("0.00255")?));
+    assert_eq!(OVERFLOW, Err(ArithmeticError::Overflow));
+
+    const BIG: crate::FixedPoint128<18> = fixnum!("1.5", FixedPoint128);
+    const BIG_RATIO: Result<crate::FixedPoint128<18>, ArithmeticError> =
+        BIG.const_rdiv(fixnum!("7", FixedPoint128), RoundMode::Ceil);
+    assert_eq!(BIG_RATIO, Ok("0.214285714285714286".parse()?));
+
+    let values = [
+        "0",
+        "1.5",
+        "-2.000000001",
+        "0.000000007",
+        "-9223372036.854775808",
+    ];
+    let modes = [RoundMode::Ceil, RoundMode::HalfEven, RoundMode::Unnecessary];
+
+    for a in &values {
+        for b in &values {
+            let (a, b) = (fp(a)?, fp(b)?);
+
+            assert_eq!(a.const_add(b), a.cadd(b));
+            assert_eq!(a.const_sub(b), a.csub(b));
+
+            for &mode in &modes {
+                assert_eq!(a.const_rmul(b, mode), a.rmul(b, mode));
+                assert_eq!(a.const_rdiv(b, mode), a.rdiv(b, mode));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[test]
 fn from_less_accurate_decimal() -> Result<()> {
     assert_eq!(FixedPoint::from_decimal(1, 0), Ok(fp("1")?));
@@ -27,21 +137,60 @@ fn from_less_accurate_decimal() -> Result<()> {
 
 #[test]
 fn from_good_str() -> Result<()> {
-    assert_eq!(fp("1")?, FixedPoint(1_000_000_000));
-    assert_eq!(fp("1.1")?, FixedPoint(1_100_000_000));
-    assert_eq!(fp("1.02")?, FixedPoint(1_020_000_000));
-    assert_eq!(fp("-1.02")?, FixedPoint(-1_020_000_000));
-    assert_eq!(fp("+1.02")?, FixedPoint(1_020_000_000));
+    assert_eq!(fp("1")?, FixedPoint::from_mantissa(1_000_000_000));
+    assert_eq!(fp("1.1")?, FixedPoint::from_mantissa(1_100_000_000));
+    assert_eq!(fp("1.02")?, FixedPoint::from_mantissa(1_020_000_000));
+    assert_eq!(fp("-1.02")?, FixedPoint::from_mantissa(-1_020_000_000));
+    assert_eq!(fp("+1.02")?, FixedPoint::from_mantissa(1_020_000_000));
     assert_eq!(
         fp("123456789.123456789")?,
-        FixedPoint(123_456_789_123_456_789)
+        FixedPoint::from_mantissa(123_456_789_123_456_789)
     );
     assert_eq!(
         fp("9223372036.854775807")?,
-        FixedPoint(9_223_372_036_854_775_807)
+        FixedPoint::from_mantissa(9_223_372_036_854_775_807)
+    );
+    assert_eq!(fp("0.1234")?, FixedPoint::from_mantissa(123_400_000));
+    assert_eq!(fp("-0.1234")?, FixedPoint::from_mantissa(-123_400_000));
+
+    Ok(())
+}
+
+#[test]
+fn from_str_with_exponent() -> Result<()> {
+    assert_eq!(fp("1.5e-3")?, fp("0.0015")?);
+    assert_eq!(fp("2E+4")?, fp("20000")?);
+    assert_eq!(fp("-7.25e2")?, fp("-725")?);
+    assert_eq!(fp("1e-9")?, FixedPoint::EPSILON);
+    assert_eq!(fp("123456789123456789e-9")?, fp("123456789.123456789")?);
+    assert_eq!(fp("9.223372036854775807e9")?, FixedPoint::MAX);
+    assert_eq!(fp("-0.00000000000000000001e+11")?, fp("-0.000000001")?);
+    assert_eq!(fp("0e99999999999999999999")?, FixedPoint::ZERO);
+    assert_eq!(fp("1.50000000000")?, fp("1.5")?);
+
+    assert_eq!(
+        FixedPoint::from_str("1.5e-9"),
+        Err(ParseFixedPointError::ExcessPrecision { digits: 10 })
+    );
+    assert!(matches!(
+        FixedPoint::from_str("1e-99999999999999999999"),
+        Err(ParseFixedPointError::ExcessPrecision { .. })
+    ));
+    assert_eq!(
+        FixedPoint::from_str("1e10"),
+        Err(ParseFixedPointError::Overflow)
+    );
+    assert_eq!(
+        FixedPoint::from_str("-1e99999999999999999999"),
+        Err(ParseFixedPointError::Overflow)
     );
-    assert_eq!(fp("0.1234")?, FixedPoint(123_400_000));
-    assert_eq!(fp("-0.1234")?, FixedPoint(-123_400_000));
+
+    type Wei = crate::FixedPoint128<18>;
+    assert_eq!(
+        "1.7e20".parse::<Wei>()?,
+        Wei::try_from(170_000_000_000_000_000_000i128)?
+    );
+    assert_eq!("-1e-18".parse::<Wei>()?, Wei::from_mantissa(-1));
 
     Ok(())
 }
@@ -65,11 +214,112 @@ fn display() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn display_flags() -> Result<()> {
+    let x = fp("-1234.5678")?;
+    let y = fp("42")?;
+
+    // Precision rounds half to even.
+    assert_eq!(format!("{:.2}", x), "-1234.57");
+    assert_eq!(format!("{:.0}", x), "-1235");
+    assert_eq!(format!("{:.12}", x), "-1234.567800000000");
+    assert_eq!(format!("{:.1}", fp("0.25")?), "0.2");
+    assert_eq!(format!("{:.1}", fp("0.35")?), "0.4");
+    assert_eq!(format!("{:.0}", fp("2.5")?), "2");
+    assert_eq!(format!("{:.1}", fp("9.96")?), "10.0");
+    assert_eq!(format!("{:.2}", y), "42.00");
+    assert_eq!(format!("{:.2}", fp("-0.001")?), "0.00");
+
+    // Width, fill, alignment, sign and zero padding.
+    assert_eq!(format!("[{:10}]", y), "[      42.0]");
+    assert_eq!(format!("[{:<10}]", y), "[42.0      ]");
+    assert_eq!(format!("[{:^10}]", y), "[   42.0   ]");
+    assert_eq!(format!("[{:*^11.2}]", x), "[*-1234.57**]");
+    assert_eq!(format!("[{:+}]", y), "[+42.0]");
+    assert_eq!(format!("[{:+}]", x), "[-1234.5678]");
+    assert_eq!(format!("[{:08.3}]", y), "[0042.000]");
+    assert_eq!(format!("[{:+09.1}]", y), "[+000042.0]");
+    assert_eq!(format!("[{:012.3}]", x), "[-0001234.568]");
+    assert_eq!(format!("[{:3}]", x), "[-1234.5678]");
+    assert_eq!(format!("[{:<10?}]", y), "[42.0      ]");
+
+    // An explicit rounding mode, the formatter's precision is ignored.
+    let rounded = |x: FixedPoint, mode| x.display_rounded(2, mode).map(|x| x.to_string());
+    assert_eq!(rounded(x, RoundMode::Floor)?, "-1234.57");
+    assert_eq!(rounded(x, RoundMode::Ceil)?, "-1234.56");
+    assert_eq!(rounded(x, RoundMode::TowardZero)?, "-1234.56");
+    assert_eq!(rounded(y, RoundMode::Unnecessary)?, "42.00");
+    assert_eq!(
+        rounded(x, RoundMode::Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+    assert_eq!(
+        format!("[{:>+9.5}]", y.display_rounded(1, RoundMode::Unnecessary)?),
+        "[    +42.0]"
+    );
+    assert_eq!(
+        format!("{:.0}", x.display_rounded(6, RoundMode::Floor)?),
+        "-1234.567800"
+    );
+
+    // Extreme values of all layouts.
+    assert_eq!(format!("{:.1}", FixedPoint::MIN), "-9223372036.9");
+    assert_eq!(
+        format!("{:.0}", crate::FixedPoint128::<18>::MIN),
+        "-170141183460469231732"
+    );
+    assert_eq!(
+        format!("{:>25.2}", crate::FixedPoint128::<18>::MAX),
+        " 170141183460469231731.69"
+    );
+    assert_eq!(
+        format!("{:.3}", crate::FixedPoint32::<4>::MAX),
+        "214748.365"
+    );
+
+    // Locale formatting honors the precision too.
+    let german = crate::locale::Locale::new()
+        .decimal_separator(',')
+        .grouping('.', &[3]);
+    assert_eq!(
+        format!("[{:>12.1}]", x.format_locale(&german)),
+        "[    -1.234,6]"
+    );
+
+    assert_eq!(
+        format!("{:015}", x.format_locale(&german)),
+        "-00001.234,5678"
+    );
+    assert_eq!(
+        format!(
+            "{:08}",
+            fp("-1.5")?.format_locale(&german.minus_sign('\u{2212}'))
+        ),
+        "\u{2212}00001,5"
+    );
+    assert_eq!(
+        format!("{:+.1}", fp("1234.56")?.format_locale(&german)),
+        "+1.234,6"
+    );
+
+    // Trailing zeros of a long precision aren't buffered.
+    assert_eq!(
+        format!("{:.90}", x.format_locale(&german)),
+        format!("-1.234,5678{}", "0".repeat(86))
+    );
+
+    Ok(())
+}
+
 #[test]
 fn from_bad_str() {
     let bad = &[
         "",
-        "7.02e5",
+        "7.02e",
+        "7.02e+",
+        "7.e5",
+        "e5",
+        "7e5.2",
         "a.12",
         "12.a",
         "13.0000000001",
@@ -86,430 +336,2373 @@ fn from_bad_str() {
 }
 
 #[test]
-#[allow(clippy::assertions_on_constants)]
-fn exp_and_coef_should_agree() {
-    assert!(FixedPoint::EXP < 0);
-    assert_eq!(COEF, 10i64.pow(-FixedPoint::EXP as u32));
-}
+fn parse_errors() {
+    use ParseFixedPointError::*;
+
+    let cases = [
+        ("", Empty),
+        (" \t", Empty),
+        ("a.12", InvalidDigit { position: 0 }),
+        (" 1.5x", InvalidDigit { position: 4 }),
+        ("12.a", InvalidDigit { position: 3 }),
+        ("1.2.3", InvalidDigit { position: 3 }),
+        ("1 000", InvalidDigit { position: 1 }),
+        ("1e", InvalidDigit { position: 2 }),
+        ("1e+x", InvalidDigit { position: 3 }),
+        ("−1", InvalidDigit { position: 0 }),
+        (".5", MissingIntegralPart),
+        ("-", MissingIntegralPart),
+        ("+e5", MissingIntegralPart),
+        ("13.0000000001", ExcessPrecision { digits: 10 }),
+        ("1.23e-8", ExcessPrecision { digits: 10 }),
+        ("-0.00000000000000000001e5", ExcessPrecision { digits: 15 }),
+        ("9223372036.854775808", Overflow),
+        ("1e10", Overflow),
+    ];
 
-#[test]
-fn cmul_overflow() {
-    let result = FixedPoint::MAX.cmul(i64::MAX);
-    assert_eq!(result, Err(ArithmeticError::Overflow));
+    for (str, expected) in &cases {
+        assert_eq!(FixedPoint::from_str(str), Err(*expected), "{:?}", str);
+    }
 
-    let result = FixedPoint::MAX.cmul(i64::MIN);
-    assert_eq!(result, Err(ArithmeticError::Overflow));
-}
+    assert_eq!(
+        fp(" \t-7.5\n").unwrap(),
+        FixedPoint::from_mantissa(-7_500_000_000)
+    );
+    assert_eq!(fp("0.00000000100").unwrap(), FixedPoint::EPSILON);
 
-macro_rules! assert_rmul {
-    ($a:expr, $b:expr, $mode:ident, $result:expr) => {{
-        let a = FixedPoint::try_from($a)?;
-        let b = FixedPoint::try_from($b)?;
+    let err = FixedPoint::from_str("12.5%").unwrap_err();
+    assert_eq!(err.to_string(), "invalid digit at byte 4");
+    assert_eq!(
+        ExcessPrecision { digits: 10 }.to_string(),
+        "precision is too high: 10 fractional digits"
+    );
+    let _: &dyn std::error::Error = &err;
+}
 
-        // Check the commutative property.
-        assert_eq!(a.rmul(b, RoundMode::$mode), b.rmul(a, RoundMode::$mode));
-        // Check the result.
+#[test]
+fn parse_rounding() -> Result<()> {
+    fn t(str: &str, mode: RoundMode, expected: &str) -> Result<()> {
         assert_eq!(
-            a.rmul(b, RoundMode::$mode),
-            Ok(FixedPoint::try_from($result)?)
+            FixedPoint::parse_rounding(str, mode)?,
+            fp(expected)?,
+            "{} {:?}",
+            str,
+            mode
         );
-    }};
-}
+        Ok(())
+    }
 
-// TODO(hrls): remove
-macro_rules! assert_rmuls {
-    ($a:expr, $b:expr, $mode:ident, $result:expr) => {{
-        let a = fp(&format!("{}", $a))?;
-        let b = fp(&format!("{}", $b))?;
+    t("0.1234567891234", RoundMode::Floor, "0.123456789")?;
+    t("0.1234567891234", RoundMode::Ceil, "0.12345679")?;
+    t("-0.1234567891234", RoundMode::Floor, "-0.12345679")?;
+    t("0.0000000015", RoundMode::HalfEven, "0.000000002")?;
+    t("0.0000000025", RoundMode::HalfEven, "0.000000002")?;
+    t("0.00000000250000000001", RoundMode::HalfEven, "0.000000003")?;
+    t("-0.0000000025", RoundMode::HalfUp, "-0.000000003")?;
+    t("-0.0000000025", RoundMode::HalfDown, "-0.000000002")?;
+    t("0.00000000000001", RoundMode::AwayFromZero, "0.000000001")?;
+    t("1.25e-9", RoundMode::HalfEven, "0.000000001")?;
+    t("1.5", RoundMode::Unnecessary, "1.5")?;
 
-        // Check the commutative property.
-        assert_eq!(a.rmul(b, RoundMode::$mode), b.rmul(a, RoundMode::$mode));
-        // Check the result.
-        assert_eq!(
-            a.rmul(b, RoundMode::$mode),
-            Ok(fp(&format!("{}", $result))?)
-        );
-    }};
-}
+    assert_eq!(
+        FixedPoint::parse_truncating("-1.9999999999")?,
+        fp("-1.999999999")?
+    );
+    assert_eq!(
+        FixedPoint::parse_rounding("0.1234567891", RoundMode::Unnecessary),
+        Err(ParseFixedPointError::ExcessPrecision { digits: 10 })
+    );
 
-#[test]
-#[allow(clippy::cognitive_complexity)]
-fn rmul_exact() -> Result<()> {
-    assert_rmul!(525, 10, Ceil, 5250);
-    assert_rmul!(525, 10, Floor, 5250);
-    assert_rmul!(-525, 10, Ceil, -5250);
-    assert_rmul!(-525, 10, Floor, -5250);
-    assert_rmul!(-525, -10, Ceil, 5250);
-    assert_rmul!(-525, -10, Floor, 5250);
-    assert_rmul!(525, -10, Ceil, -5250);
-    assert_rmul!(525, -10, Floor, -5250);
-    assert_rmuls!(525, "0.0001", Ceil, "0.0525");
-    assert_rmuls!(525, "0.0001", Floor, "0.0525");
-    assert_rmuls!(-525, "0.0001", Ceil, "-0.0525");
-    assert_rmuls!(-525, "0.0001", Floor, "-0.0525");
-    assert_rmuls!(-525, "-0.0001", Ceil, "0.0525");
-    assert_rmuls!(-525, "-0.0001", Floor, "0.0525");
-    assert_rmul!(FixedPoint::MAX, 1, Ceil, FixedPoint::MAX);
-    assert_rmul!(FixedPoint::MAX, 1, Floor, FixedPoint::MAX);
-    assert_rmuls!(
-        FixedPoint(i64::MAX / 10 * 10),
-        "0.1",
-        Ceil,
-        FixedPoint(i64::MAX / 10)
+    // Overflow and malformed input are still rejected.
+    assert_eq!(
+        FixedPoint::parse_rounding("9223372036.8547758075", RoundMode::HalfUp),
+        Err(ParseFixedPointError::Overflow)
     );
-    assert_rmuls!(
-        FixedPoint(i64::MAX / 10 * 10),
-        "0.1",
-        Floor,
-        FixedPoint(i64::MAX / 10)
+    assert_eq!(
+        FixedPoint::parse_truncating("9223372036.8547758075")?,
+        FixedPoint::MAX
+    );
+    assert_eq!(
+        FixedPoint::parse_truncating("1.23x"),
+        Err(ParseFixedPointError::InvalidDigit { position: 4 })
+    );
+    assert_eq!(
+        FixedPoint::parse_truncating(".5"),
+        Err(ParseFixedPointError::MissingIntegralPart)
+    );
+
+    assert_eq!(
+        crate::FixedPoint128::<18>::parse_rounding("0.1234567890123456789", RoundMode::HalfEven)?
+            .mantissa(),
+        123_456_789_012_345_679
     );
-    assert_rmuls!(1, "0.000000001", Ceil, "0.000000001");
-    assert_rmuls!(1, "0.000000001", Floor, "0.000000001");
-    assert_rmuls!(-1, "-0.000000001", Ceil, "0.000000001");
-    assert_rmuls!(-1, "-0.000000001", Floor, "0.000000001");
 
     Ok(())
 }
 
 #[test]
-fn rmul_round() -> Result<()> {
-    assert_rmuls!("0.1", "0.000000001", Ceil, "0.000000001");
-    assert_rmuls!("0.1", "0.000000001", Floor, 0);
-    assert_rmuls!("-0.1", "0.000000001", Ceil, 0);
-    assert_rmuls!("-0.1", "0.000000001", Floor, "-0.000000001");
-    assert_rmuls!("-0.1", "-0.000000001", Ceil, "0.000000001");
-    assert_rmuls!("-0.1", "-0.000000001", Floor, 0);
-    assert_rmuls!("0.000000001", "0.000000001", Ceil, "0.000000001");
-    assert_rmuls!("0.000000001", "0.000000001", Floor, 0);
-    assert_rmuls!("-0.000000001", "0.000000001", Ceil, 0);
-    assert_rmuls!("-0.000000001", "0.000000001", Floor, "-0.000000001");
+fn parse_options() -> Result<()> {
+    use crate::parse::ParseOptions;
+    use ParseFixedPointError::*;
+
+    let strict = ParseOptions::strict();
+    let lenient = ParseOptions::lenient();
+
+    let cases = [
+        ("1.5", "1.5", Ok("1.5")),
+        ("-0.5", "-0.5", Ok("-0.5")),
+        ("+1.5", "1.5", Err(InvalidDigit { position: 0 })),
+        (".5", "0.5", Err(MissingIntegralPart)),
+        ("-.5", "-0.5", Err(MissingIntegralPart)),
+        ("5.", "5", Err(InvalidDigit { position: 2 })),
+        ("1_000.25", "1000.25", Err(InvalidDigit { position: 1 })),
+        ("1_000_000", "1000000", Err(InvalidDigit { position: 1 })),
+        ("0.000_001", "0.000001", Err(InvalidDigit { position: 5 })),
+        ("\u{2212}1.5", "-1.5", Err(InvalidDigit { position: 0 })),
+        ("12.5%", "0.125", Err(InvalidDigit { position: 4 })),
+        ("-150%", "-1.5", Err(InvalidDigit { position: 4 })),
+        ("1.5e-3", "0.0015", Err(InvalidDigit { position: 3 })),
+        (" 1.5 ", "1.5", Err(InvalidDigit { position: 0 })),
+        ("00.5", "0.5", Err(InvalidDigit { position: 0 })),
+        ("-007", "-7", Err(InvalidDigit { position: 1 })),
+        ("-0", "0", Err(InvalidDigit { position: 0 })),
+        ("-0.000", "0", Err(InvalidDigit { position: 0 })),
+        ("0.500", "0.5", Ok("0.5")),
+        ("-0.05", "-0.05", Ok("-0.05")),
+    ];
 
-    Ok(())
-}
+    for (str, lenient_expected, strict_expected) in &cases {
+        let strict_expected = strict_expected.map(|expected| fp(expected).unwrap());
+        assert_eq!(
+            FixedPoint::parse_with(str, strict),
+            strict_expected,
+            "{:?}",
+            str
+        );
+        assert_eq!(
+            FixedPoint::parse_with(str, lenient)?,
+            fp(lenient_expected)?,
+            "{:?}",
+            str
+        );
+    }
 
-#[test]
-fn rmul_overflow() -> Result<()> {
-    let a = FixedPoint::MAX;
-    let b = fp("1.1")?;
-    assert_eq!(a.rmul(b, RoundMode::Ceil), Err(ArithmeticError::Overflow));
+    // Malformed in both profiles.
+    let bad = [
+        ("", Empty),
+        (".", MissingIntegralPart),
+        ("_1", InvalidDigit { position: 0 }),
+        ("1_", InvalidDigit { position: 1 }),
+        ("1__0", InvalidDigit { position: 1 }),
+        ("1._5", InvalidDigit { position: 2 }),
+        ("%", InvalidDigit { position: 0 }),
+        ("--1", InvalidDigit { position: 1 }),
+        ("1.0000000001", ExcessPrecision { digits: 10 }),
+    ];
 
-    let a = fp("140000")?;
-    assert_eq!(a.rmul(a, RoundMode::Ceil), Err(ArithmeticError::Overflow));
+    for (str, expected) in &bad {
+        for &options in &[strict, lenient] {
+            assert_eq!(
+                FixedPoint::parse_with(str, options),
+                Err(*expected),
+                "{:?}",
+                str
+            );
+        }
+    }
 
-    let a = fp("-140000")?;
-    let b = fp("140000")?;
-    assert_eq!(a.rmul(b, RoundMode::Ceil), Err(ArithmeticError::Overflow));
+    assert_eq!(
+        FixedPoint::parse_with("1.5%%", lenient),
+        Err(InvalidDigit { position: 4 })
+    );
+
+    // `FromStr` uses the default options.
+    assert_eq!(ParseOptions::default(), ParseOptions::new());
+    assert_eq!(fp("-007.50")?, fp("-7.5")?);
+    assert_eq!(fp("-0.0")?, FixedPoint::ZERO);
+    assert_eq!(
+        FixedPoint::parse_with(" +1.5e1 ", ParseOptions::new())?,
+        fp(" +1.5e1 ")?
+    );
+
+    // Profiles can be adjusted.
+    let options = strict.underscores(true).rounding(RoundMode::Floor);
+    assert_eq!(
+        FixedPoint::parse_with("1_000.123_456_789_9", options)?,
+        fp("1000.123456789")?
+    );
+    assert_eq!(
+        FixedPoint::parse_with(" 1", lenient.whitespace(false)),
+        Err(InvalidDigit { position: 0 })
+    );
+    assert_eq!(
+        FixedPoint::parse_with("0.0000000125%", lenient.rounding(RoundMode::HalfUp))?,
+        FixedPoint::ZERO
+    );
 
     Ok(())
 }
 
 #[test]
-fn rdiv_exact() -> Result<()> {
-    let (numer, denom) = (fp("5")?, fp("2")?);
-    let result = fp("2.5")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+fn locales() -> Result<()> {
+    use crate::locale::Locale;
+    use ParseFixedPointError::*;
+
+    let german = Locale::new().decimal_separator(',').grouping('.', &[3]);
+    let french = Locale::new()
+        .decimal_separator(',')
+        .grouping('\u{a0}', &[3])
+        .minus_sign('\u{2212}');
+    let indian = Locale::new().grouping(',', &[3, 2]);
+
+    let cases = [
+        (german, "-1234567.89", "-1.234.567,89"),
+        (german, "123.5", "123,5"),
+        (german, "1000", "1.000,0"),
+        (french, "-1234.5", "\u{2212}1\u{a0}234,5"),
+        (french, "0.001", "0,001"),
+        (indian, "-1234567.89", "-12,34,567.89"),
+        (indian, "123456789", "12,34,56,789.0"),
+        (indian, "12345", "12,345.0"),
+        (Locale::new(), "-1234567.89", "-1234567.89"),
+    ];
 
-    let (numer, denom) = (fp("-5")?, fp("2")?);
-    let result = fp("-2.5")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+    for (locale, value, expected) in &cases {
+        let value = fp(value)?;
+        assert_eq!(value.format_locale(locale).to_string(), *expected);
+        assert_eq!(FixedPoint::parse_locale(expected, locale)?, value);
+    }
 
-    let (numer, denom) = (fp("-5")?, fp("-2")?);
-    let result = fp("2.5")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+    // Group separators are optional, but must be placed correctly.
+    assert_eq!(
+        FixedPoint::parse_locale("1234567,5", &german)?,
+        fp("1234567.5")?
+    );
+    assert_eq!(
+        FixedPoint::parse_locale("1234567.5", &indian)?,
+        fp("1234567.5")?
+    );
 
-    let (numer, denom) = (fp("5")?, fp("-2")?);
-    let result = fp("-2.5")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+    let bad = [
+        (german, "1.23.4,5", InvalidDigit { position: 4 }),
+        (german, "12345.678", InvalidDigit { position: 5 }),
+        (german, "1,234.5", InvalidDigit { position: 5 }),
+        (german, "1.234,5.6", InvalidDigit { position: 7 }),
+        (german, ".5", InvalidDigit { position: 0 }),
+        (german, ",5", MissingIntegralPart),
+        (indian, "1,234,567.5", InvalidDigit { position: 1 }),
+        (indian, "1234,567.5", InvalidDigit { position: 4 }),
+        (french, "-1", InvalidDigit { position: 0 }),
+        (french, "1\u{a0}23,5", InvalidDigit { position: 1 }),
+    ];
 
-    let (numer, denom) = (FixedPoint::MAX, FixedPoint::MAX);
-    let result = fp("1")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+    for (locale, str, expected) in &bad {
+        assert_eq!(
+            FixedPoint::parse_locale(str, locale),
+            Err(*expected),
+            "{:?}",
+            str
+        );
+    }
 
-    let (numer, denom) = (fp("5")?, fp("0.2")?);
-    let result = fp("25")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+    // Width and alignment apply to the whole string.
+    assert_eq!(
+        format!("[{:>14}]", fp("-1234.5")?.format_locale(&french)),
+        "[      \u{2212}1\u{a0}234,5]"
+    );
 
-    let (numer, denom) = (fp("0.00000001")?, fp("10")?);
-    let result = fp("0.000000001")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
-
-    let (numer, denom) = (fp("0.00000001")?, fp("0.1")?);
-    let result = fp("0.0000001")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+    // Every value round-trips in all layouts.
+    let values = ["0", "-0.000000001", "999.999", "-1000000.5"];
+    for locale in &[german, french, indian] {
+        for value in values.iter().map(|value| fp(value)) {
+            let value = value?;
+            let formatted = value.format_locale(locale).to_string();
+            assert_eq!(FixedPoint::parse_locale(&formatted, locale)?, value);
+        }
+
+        for &value in &[FixedPoint::MIN, FixedPoint::MAX] {
+            let formatted = value.format_locale(locale).to_string();
+            assert_eq!(FixedPoint::parse_locale(&formatted, locale)?, value);
+        }
+
+        for &value in &[crate::FixedPoint128::<18>::MIN, crate::FixedPoint128::MAX] {
+            let formatted = value.format_locale(locale).to_string();
+            assert_eq!(
+                crate::FixedPoint128::parse_locale(&formatted, locale)?,
+                value
+            );
+        }
+
+        for &value in &[crate::FixedPoint32::<4>::MIN, crate::FixedPoint32::MAX] {
+            let formatted = value.format_locale(locale).to_string();
+            assert_eq!(
+                crate::FixedPoint32::parse_locale(&formatted, locale)?,
+                value
+            );
+        }
+    }
 
     Ok(())
 }
 
 #[test]
-fn rdiv_i64() -> Result<()> {
-    fn assert_rdiv(a: &str, b: i64, mode: RoundMode, expected: &str) -> Result<()> {
-        let a = fp(a)?;
-        let expected = fp(expected)?;
-        assert_eq!(a.rdiv(b, mode).unwrap(), expected);
-        Ok(())
-    }
-
-    assert_rdiv("2.4", 2, RoundMode::Ceil, "1.2")?;
-    assert_rdiv("7", 3, RoundMode::Floor, "2.333333333")?;
-    assert_rdiv("7", 3, RoundMode::Ceil, "2.333333334")?;
-    assert_rdiv("-7", 3, RoundMode::Floor, "-2.333333334")?;
-    assert_rdiv("-7", 3, RoundMode::Ceil, "-2.333333333")?;
-    assert_rdiv("-7", -3, RoundMode::Floor, "2.333333333")?;
-    assert_rdiv("-7", -3, RoundMode::Ceil, "2.333333334")?;
-    assert_rdiv("7", -3, RoundMode::Floor, "-2.333333334")?;
-    assert_rdiv("7", -3, RoundMode::Ceil, "-2.333333333")?;
-    assert_rdiv("0", 5, RoundMode::Ceil, "0")?;
-    assert_rdiv("0.000000003", 2, RoundMode::Ceil, "0.000000002")?;
-    assert_rdiv("0.000000003", 2, RoundMode::Floor, "0.000000001")?;
-    assert_rdiv("0.000000003", 7, RoundMode::Floor, "0")?;
-    assert_rdiv("0.000000003", 7, RoundMode::Ceil, "0.000000001")?;
-    assert_rdiv("0.000000001", 7, RoundMode::Ceil, "0.000000001")?;
-    Ok(())
+#[should_panic(expected = "the grouping separator is the same as the decimal one")]
+fn locale_with_same_separators() {
+    // `1.234.5` would be ambiguous.
+    let _ = crate::locale::Locale::new().grouping('.', &[3]);
 }
 
 #[test]
-fn rdiv_round() -> Result<()> {
-    let (numer, denom) = (fp("100")?, fp("3")?);
-    let ceil = fp("33.333333334")?;
-    let floor = fp("33.333333333")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(ceil));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(floor));
-
-    let (numer, denom) = (fp("-100")?, fp("3")?);
-    let ceil = fp("-33.333333333")?;
-    let floor = fp("-33.333333334")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(ceil));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(floor));
-
-    let (numer, denom) = (fp("-100")?, fp("-3")?);
-    let ceil = fp("33.333333334")?;
-    let floor = fp("33.333333333")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(ceil));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(floor));
-
-    let (numer, denom) = (fp("100")?, fp("-3")?);
-    let ceil = fp("-33.333333333")?;
-    let floor = fp("-33.333333334")?;
-    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(ceil));
-    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(floor));
+#[should_panic(expected = "the minus sign is the grouping separator")]
+fn locale_with_minus_as_separator() {
+    let _ = crate::locale::Locale::new()
+        .grouping(' ', &[3])
+        .minus_sign(' ');
+}
 
-    Ok(())
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn exp_and_coef_should_agree() {
+    assert!(FixedPoint::EXP < 0);
+    assert_eq!(FixedPoint::COEF, 10i64.pow(-FixedPoint::EXP as u32));
 }
 
 #[test]
-fn rdiv_division_by_zero() -> Result<()> {
+fn other_precisions() -> Result<()> {
+    type Cents = crate::FixedPoint<2>;
+    type Atto = crate::FixedPoint<18>;
+
+    let a: Cents = "12.34".parse()?;
+    assert_eq!(a.mantissa(), 1234);
+    assert_eq!(format!("{}", a), "12.34");
+    assert!("12.345".parse::<Cents>().is_err());
+    assert_eq!(Cents::EXP, -2);
+    assert_eq!(Cents::ONE, Cents::from(1));
+    assert_eq!(Cents::from_decimal(5, 3), Ok(Cents::from_mantissa(500_000)));
     assert_eq!(
-        FixedPoint::MAX.rdiv(FixedPoint::ZERO, RoundMode::Ceil),
-        Err(ArithmeticError::DivisionByZero)
+        Cents::from_decimal(5, -3),
+        Err(FixedPointFromDecimalError::UnsupportedExponent)
+    );
+    assert_eq!(
+        Cents::from_decimal(1, i32::MAX),
+        Err(FixedPointFromDecimalError::UnsupportedExponent)
+    );
+    assert_eq!(
+        Cents::from_decimal(1, i32::MIN),
+        Err(FixedPointFromDecimalError::UnsupportedExponent)
+    );
+    assert_eq!(
+        crate::FixedPoint128::<18>::from_decimal(1, i32::MAX),
+        Err(FixedPointFromDecimalError::UnsupportedExponent)
     );
 
-    Ok(())
-}
+    let b: Cents = "0.07".parse()?;
+    assert_eq!(a.rmul(b, RoundMode::Floor)?, "0.86".parse()?);
+    assert_eq!(a.rmul(b, RoundMode::Ceil)?, "0.87".parse()?);
+    assert_eq!(a.rdiv(b, RoundMode::Floor)?, "176.28".parse()?);
+    assert_eq!(b.next_power_of_ten()?, "0.1".parse()?);
 
-#[test]
-fn rdiv_overflow() -> Result<()> {
+    let c: Atto = "1.000000000000000001".parse()?;
+    assert_eq!(c.mantissa(), 1_000_000_000_000_000_001);
+    assert_eq!(format!("{}", c), "1.000000000000000001");
+    assert_eq!(c.rmul(c, RoundMode::Ceil)?, "1.000000000000000003".parse()?);
     assert_eq!(
-        FixedPoint::MAX.rdiv(fp("0.5")?, RoundMode::Ceil),
-        Err(ArithmeticError::Overflow)
+        Atto::ONE
+            .rdiv(Atto::try_from(3i64)?, RoundMode::Floor)?
+            .mantissa(),
+        333_333_333_333_333_333
     );
+    assert!(Atto::try_from(10i64).is_err());
+
+    let d = crate::FixedPoint::<0>::try_from(42i64)?;
+    assert_eq!(d.mantissa(), 42);
+    assert_eq!(format!("{}", d), "42.0");
+
     Ok(())
 }
 
 #[test]
-fn float_mul() {
-    let a = FixedPoint::try_from(525).unwrap();
-    let b = FixedPoint::try_from(10).unwrap();
-    assert_eq!(a.rmul(b, Ceil), Ok(FixedPoint::try_from(5250).unwrap()));
-
-    let a = FixedPoint::try_from(525).unwrap();
-    let b = FixedPoint::from_str("0.0001").unwrap();
-    assert_eq!(a.rmul(b, Ceil), Ok(FixedPoint::from_str("0.0525").unwrap()));
-
-    let a = FixedPoint::MAX;
-    let b = FixedPoint::try_from(1).unwrap();
-    assert_eq!(a.rmul(b, Ceil), Ok(FixedPoint::MAX));
+fn cmul_overflow() {
+    let result = FixedPoint::MAX.cmul(i64::MAX);
+    assert_eq!(result, Err(ArithmeticError::Overflow));
 
-    let a = FixedPoint(i64::MAX / 10 * 10);
-    let b = FixedPoint::from_str("0.1").unwrap();
-    assert_eq!(a.rmul(b, Ceil), Ok(FixedPoint(i64::MAX / 10)));
+    let result = FixedPoint::MAX.cmul(i64::MIN);
+    assert_eq!(result, Err(ArithmeticError::Overflow));
 }
 
 #[test]
-fn float_mul_overflow() {
-    let a = FixedPoint::try_from(140_000).unwrap();
-    assert!(a.rmul(a, Ceil).is_err());
+fn cmul_exact() -> Result<()> {
+    assert_eq!(fp("1.5")?.cmul(fp("-0.25")?), Ok(fp("-0.375")?));
+    assert_eq!(fp("0.00001")?.cmul(fp("0.0001")?), Ok(fp("0.000000001")?));
+    assert_eq!(
+        fp("0.00001")?.cmul(fp("0.00001")?),
+        Err(ArithmeticError::Inexact)
+    );
+    assert_eq!(
+        FixedPoint::MAX.cmul(fp("2")?),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(FixedPoint::MAX.cmul(FixedPoint::ONE), Ok(FixedPoint::MAX));
 
-    let a = FixedPoint::try_from(-140_000).unwrap();
-    let b = FixedPoint::try_from(140_000).unwrap();
-    assert!(a.rmul(b, Ceil).is_err());
+    Ok(())
 }
 
 #[test]
-fn half_sum() -> Result<()> {
-    fn t(a: &str, b: &str, r: &str) -> Result<()> {
-        let a = fp(a)?;
-        let b = fp(b)?;
-        let r = fp(r)?;
-        assert_eq!(FixedPoint::half_sum(a, b), r);
-        Ok(())
-    }
+fn cdiv_exact() -> Result<()> {
+    assert_eq!(fp("1")?.cdiv(fp("8")?), Ok(fp("0.125")?));
+    assert_eq!(fp("-7.5")?.cdiv(fp("2.5")?), Ok(fp("-3")?));
+    assert_eq!(fp("1")?.cdiv(fp("3")?), Err(ArithmeticError::Inexact));
+    assert_eq!(
+        fp("1")?.cdiv(FixedPoint::ZERO),
+        Err(ArithmeticError::DivisionByZero)
+    );
+    assert_eq!(
+        FixedPoint::MAX.cdiv(fp("0.5")?),
+        Err(ArithmeticError::Overflow)
+    );
 
-    t("1", "3", "2")?;
-    t("1", "2", "1.5")?;
-    t("9000", "9050", "9025")?;
-    t("9000", "-9000", "0")?;
-    t("9000000000", "9000000002", "9000000001")?;
-    t(
-        "9000000000.000000001",
-        "-9000000000.000000005",
-        "-0.000000002",
-    )?;
-    t("7.123456789", "7.123456788", "7.123456788")?;
+    assert_eq!(fp("7.5")?.cdiv(-3), Ok(fp("-2.5")?));
+    assert_eq!(fp("7")?.cdiv(3), Err(ArithmeticError::Inexact));
+    assert_eq!(fp("7")?.cdiv(0), Err(ArithmeticError::DivisionByZero));
+    assert_eq!(FixedPoint::MIN.cdiv(-1), Err(ArithmeticError::Overflow));
+
+    type Wei = crate::FixedPoint128<18>;
+    assert_eq!(
+        Wei::from(1).cdiv(Wei::from(4))?.cmul(Wei::from(-4)),
+        Ok(Wei::from(-1))
+    );
+    assert_eq!(Wei::from(1).cdiv(3), Err(ArithmeticError::Inexact));
+
+    type Percent = crate::FixedPoint32<4>;
+    assert_eq!(
+        Percent::from_mantissa(5).cmul(Percent::from_mantissa(5_000)),
+        Err(ArithmeticError::Inexact)
+    );
+    assert_eq!(
+        Percent::from_mantissa(5).cdiv(Percent::from_mantissa(5_000)),
+        Ok(Percent::from_mantissa(10))
+    );
 
     Ok(())
 }
 
 #[test]
-#[allow(clippy::many_single_char_names)]
-fn integral() -> Result<()> {
-    let a = fp("0.0001")?;
-    assert_eq!(a.integral(RoundMode::Floor), 0);
-    assert_eq!(a.integral(RoundMode::Ceil), 1);
+fn saturating() -> Result<()> {
+    use crate::ops::{SaturatingAdd, SaturatingDiv, SaturatingMul, SaturatingSub};
 
-    let b = fp("-0.0001")?;
-    assert_eq!(b.integral(RoundMode::Floor), -1);
-    assert_eq!(b.integral(RoundMode::Ceil), 0);
+    let (max, min) = (FixedPoint::MAX, FixedPoint::MIN);
 
-    let c = FixedPoint::ZERO;
-    assert_eq!(c.integral(RoundMode::Floor), 0);
-    assert_eq!(c.integral(RoundMode::Ceil), 0);
+    assert_eq!(fp("1.5")?.saturating_add(fp("2")?), fp("3.5")?);
+    assert_eq!(max.saturating_add(FixedPoint::EPSILON), max);
+    assert_eq!(min.saturating_add(FixedPoint::MINUS_ONE), min);
+    assert_eq!(min.saturating_sub(FixedPoint::EPSILON), min);
+    assert_eq!(max.saturating_sub(FixedPoint::MINUS_ONE), max);
 
-    let d = fp("2.0001")?;
-    assert_eq!(d.integral(RoundMode::Floor), 2);
-    assert_eq!(d.integral(RoundMode::Ceil), 3);
+    let big = fp("100000")?;
+    assert_eq!(big.saturating_rmul(big, RoundMode::Floor), Ok(max));
+    assert_eq!(big.cneg()?.saturating_rmul(big, RoundMode::Floor), Ok(min));
+    assert_eq!(
+        big.cneg()?.saturating_rmul(big.cneg()?, RoundMode::Ceil),
+        Ok(max)
+    );
+    assert_eq!(
+        fp("0.1")?.saturating_rmul(fp("0.000000001")?, RoundMode::Ceil),
+        Ok(FixedPoint::EPSILON)
+    );
+    assert_eq!(
+        fp("0.1")?.saturating_rmul(fp("0.000000001")?, RoundMode::Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
 
-    let e = fp("-2.0001")?;
-    assert_eq!(e.integral(RoundMode::Floor), -3);
-    assert_eq!(e.integral(RoundMode::Ceil), -2);
+    let tiny = fp("0.001")?;
+    assert_eq!(
+        big.saturating_rdiv(tiny, RoundMode::Floor),
+        Ok(fp("100000000")?)
+    );
+    assert_eq!(max.saturating_rdiv(tiny, RoundMode::Floor), Ok(max));
+    assert_eq!(max.saturating_rdiv(tiny.cneg()?, RoundMode::Floor), Ok(min));
+    assert_eq!(min.saturating_rdiv(tiny.cneg()?, RoundMode::Floor), Ok(max));
+    assert_eq!(
+        max.saturating_rdiv(FixedPoint::ZERO, RoundMode::Floor),
+        Err(ArithmeticError::DivisionByZero)
+    );
+
+    let a = Saturating(fp("2.5")?);
+    let b = Saturating(fp("0.000000003")?);
+    assert_eq!(a + b, Saturating(fp("2.500000003")?));
+    assert_eq!(a - b, Saturating(fp("2.499999997")?));
+    assert_eq!(b * Saturating(fp("0.5")?), Saturating(fp("0.000000002")?));
+    assert_eq!(b / Saturating(fp("2")?), Saturating(fp("0.000000002")?));
+    assert_eq!(-a, Saturating(fp("-2.5")?));
+    assert_eq!(-Saturating(min), Saturating(max));
+    assert_eq!(Saturating(max) + a, Saturating(max));
+    assert_eq!(Saturating(min) - a, Saturating(min));
+    assert_eq!(Saturating(max) * -a, Saturating(min));
+    assert_eq!(Saturating(min) / b, Saturating(min));
+
+    let mut c = a;
+    c += Saturating(max);
+    assert_eq!(c, Saturating(max));
+    c -= a;
+    c *= Saturating(fp("-1")?);
+    c /= Saturating(fp("0.5")?);
+    assert_eq!(c, Saturating(min));
+    assert_eq!(format!("{}", a), "2.5");
 
     Ok(())
 }
 
 #[test]
-fn round_towards_zero_by() -> Result<()> {
-    let a = fp("1234.56789")?;
-    assert_eq!(a.round_towards_zero_by(fp("100")?), fp("1200")?);
-    assert_eq!(a.round_towards_zero_by(fp("10")?), fp("1230")?);
-    assert_eq!(a.round_towards_zero_by(fp("1")?), fp("1234")?);
-    assert_eq!(a.round_towards_zero_by(fp("0.1")?), fp("1234.5")?);
-    assert_eq!(a.round_towards_zero_by(fp("0.01")?), fp("1234.56")?);
-    assert_eq!(a.round_towards_zero_by(fp("0.001")?), fp("1234.567")?);
-    assert_eq!(a.round_towards_zero_by(fp("0.0001")?), fp("1234.5678")?);
-    assert_eq!(a.round_towards_zero_by(fp("0.00001")?), fp("1234.56789")?);
-
-    let b = fp("-1234.56789")?;
-    assert_eq!(b.round_towards_zero_by(fp("100")?), fp("-1200")?);
-    assert_eq!(b.round_towards_zero_by(fp("10")?), fp("-1230")?);
-    assert_eq!(b.round_towards_zero_by(fp("1")?), fp("-1234")?);
-    assert_eq!(b.round_towards_zero_by(fp("0.1")?), fp("-1234.5")?);
-    assert_eq!(b.round_towards_zero_by(fp("0.01")?), fp("-1234.56")?);
-    assert_eq!(b.round_towards_zero_by(fp("0.001")?), fp("-1234.567")?);
-    assert_eq!(b.round_towards_zero_by(fp("0.0001")?), fp("-1234.5678")?);
-    assert_eq!(b.round_towards_zero_by(fp("0.00001")?), fp("-1234.56789")?);
-
-    Ok(())
+#[should_panic]
+fn saturating_division_by_zero() {
+    let _ = Saturating(FixedPoint::ONE) / Saturating(FixedPoint::ZERO);
 }
 
 #[test]
-#[allow(clippy::cognitive_complexity)]
-fn next_power_of_ten() -> Result<()> {
-    assert_eq!(fp("0")?.next_power_of_ten()?, fp("0.000000001")?);
-    assert_eq!(fp("0.000000001")?.next_power_of_ten()?, fp("0.000000001")?);
-    assert_eq!(fp("0.000000002")?.next_power_of_ten()?, fp("0.00000001")?);
-    assert_eq!(fp("0.000000009")?.next_power_of_ten()?, fp("0.00000001")?);
-    assert_eq!(fp("0.00000001")?.next_power_of_ten()?, fp("0.00000001")?);
-    assert_eq!(fp("0.00000002")?.next_power_of_ten()?, fp("0.0000001")?);
-    assert_eq!(fp("0.1")?.next_power_of_ten()?, fp("0.1")?);
-    assert_eq!(fp("0.100000001")?.next_power_of_ten()?, fp("1")?);
-    assert_eq!(fp("1")?.next_power_of_ten()?, fp("1")?);
-    assert_eq!(fp("2")?.next_power_of_ten()?, fp("10")?);
-    assert_eq!(fp("1234567")?.next_power_of_ten()?, fp("10000000")?);
+fn wrapping() -> Result<()> {
+    use crate::ops::{WrappingAdd, WrappingDiv, WrappingMul, WrappingSub};
+
+    let (max, min) = (FixedPoint::MAX, FixedPoint::MIN);
+
+    assert_eq!(fp("1.5")?.wrapping_add(fp("2")?), fp("3.5")?);
+    assert_eq!(max.wrapping_add(FixedPoint::EPSILON), min);
+    assert_eq!(min.wrapping_sub(FixedPoint::EPSILON), max);
+    assert_eq!(fp("1.5")?.wrapping_sub(fp("2")?), fp("-0.5")?);
+
+    // The exact result is rounded first, then wrapped.
+    let big = fp("100000")?;
     assert_eq!(
-        fp("923372036.854775807")?.next_power_of_ten()?,
-        fp("1000000000")?
+        big.wrapping_rmul(big, RoundMode::Floor),
+        Ok(FixedPoint::from_mantissa(
+            10_000_000_000_000_000_000u64 as i64
+        ))
     );
     assert_eq!(
-        fp("9223372036.854775807")?.next_power_of_ten(),
-        Err(ArithmeticError::Overflow)
+        max.wrapping_rmul(fp("-2")?, RoundMode::Floor),
+        Ok(FixedPoint::from_mantissa(2))
+    );
+    assert_eq!(
+        fp("0.1")?.wrapping_rmul(fp("0.000000001")?, RoundMode::Ceil),
+        Ok(FixedPoint::EPSILON)
+    );
+    assert_eq!(
+        fp("0.1")?.wrapping_rmul(fp("0.000000001")?, RoundMode::Unnecessary),
+        Err(ArithmeticError::Inexact)
     );
 
+    let tiny = fp("0.001")?;
     assert_eq!(
-        fp("-0.000000001")?.next_power_of_ten()?,
-        fp("-0.000000001")?
+        max.wrapping_rdiv(tiny, RoundMode::Floor),
+        Ok(FixedPoint::from_mantissa(i64::MAX.wrapping_mul(1000)))
     );
-    assert_eq!(fp("-0.000000002")?.next_power_of_ten()?, fp("-0.00000001")?);
-    assert_eq!(fp("-0.000000009")?.next_power_of_ten()?, fp("-0.00000001")?);
-    assert_eq!(fp("-0.00000001")?.next_power_of_ten()?, fp("-0.00000001")?);
-    assert_eq!(fp("-0.00000002")?.next_power_of_ten()?, fp("-0.0000001")?);
-    assert_eq!(fp("-0.1")?.next_power_of_ten()?, fp("-0.1")?);
-    assert_eq!(fp("-0.2")?.next_power_of_ten()?, fp("-1")?);
-    assert_eq!(fp("-1")?.next_power_of_ten()?, fp("-1")?);
-    assert_eq!(fp("-0.100000001")?.next_power_of_ten()?, fp("-1")?);
-    assert_eq!(fp("-1234567")?.next_power_of_ten()?, fp("-10000000")?);
     assert_eq!(
-        fp("-923372036.854775808")?.next_power_of_ten()?,
-        fp("-1000000000")?
+        fp("1")?.wrapping_rdiv(fp("3")?, RoundMode::Ceil),
+        Ok(fp("0.333333334")?)
     );
     assert_eq!(
-        fp("-9223372036.854775807")?.next_power_of_ten(),
-        Err(ArithmeticError::Overflow)
+        max.wrapping_rdiv(FixedPoint::ZERO, RoundMode::Floor),
+        Err(ArithmeticError::DivisionByZero)
     );
+
+    type Wei = crate::FixedPoint128<18>;
+    assert_eq!(Wei::MAX.wrapping_add(Wei::EPSILON), Wei::MIN);
     assert_eq!(
-        fp("-9223372036.854775808")?.next_power_of_ten(),
-        Err(ArithmeticError::Overflow)
+        Wei::MAX.wrapping_rmul(Wei::from(2), RoundMode::Floor),
+        Ok(Wei::from_mantissa(-2))
+    );
+    assert_eq!(
+        Wei::MIN.wrapping_rdiv(Wei::from(-1), RoundMode::Floor),
+        Ok(Wei::MIN)
+    );
+
+    type Percent = crate::FixedPoint32<4>;
+    assert_eq!(
+        Percent::MAX.wrapping_rmul(Percent::from_mantissa(20_000), RoundMode::Floor),
+        Ok(Percent::from_mantissa(-2))
     );
 
     Ok(())
 }
 
 #[test]
-fn rounding_to_i64() {
-    fn t(x: &str, r: i64) {
-        let f = FixedPoint::from_str(x).unwrap();
-        assert_eq!(f.rounding_to_i64(), r);
-    }
+#[cfg(feature = "std-ops")]
+fn std_ops() -> Result<()> {
+    let a = fp("2.5")?;
+    let b = fp("0.000000003")?;
+
+    assert_eq!(a + b, fp("2.500000003")?);
+    assert_eq!(a - b, fp("2.499999997")?);
+    assert_eq!(b * fp("0.5")?, fp("0.000000002")?);
+    assert_eq!(b * fp("-0.5")?, fp("-0.000000002")?);
+    assert_eq!(b / fp("2")?, fp("0.000000002")?);
+    assert_eq!(fp("1")? / fp("3")?, fp("0.333333333")?);
+    assert_eq!(-a, fp("-2.5")?);
+
+    let mut c = a;
+    c += a;
+    c -= b;
+    c *= fp("2")?;
+    c /= fp("-4")?;
+    // -2.4999999985 is a tie, rounded to even.
+    assert_eq!(c, fp("-2.499999998")?);
+
+    type Wei = crate::FixedPoint128<18>;
+    assert_eq!(Wei::from(3) * Wei::from(4) - Wei::ONE, Wei::from(11));
+    type Percent = crate::FixedPoint32<4>;
+    assert_eq!(
+        Percent::from_mantissa(5) / Percent::from_mantissa(20_000),
+        Percent::from_mantissa(2)
+    );
 
-    t("0", 0);
-    t("42", 42);
-    t("1.4", 1);
-    t("1.6", 2);
-    t("-1.4", -1);
-    t("-1.6", -2);
-    t("0.4999", 0);
-    t("0.5", 1);
-    t("0.5001", 1);
+    Ok(())
 }
 
 #[test]
-fn to_f64() {
-    fn t(x: &str, expected: f64) {
-        let f = FixedPoint::from_str(x).unwrap();
-        let actual = f.to_f64();
-        assert_eq!(actual.to_string(), expected.to_string());
+#[cfg(feature = "std-ops")]
+#[should_panic(expected = "overflow")]
+fn std_ops_overflow() {
+    let _ = FixedPoint::MAX + FixedPoint::EPSILON;
+}
+
+#[test]
+#[cfg(feature = "std-ops")]
+#[should_panic(expected = "attempt to divide by zero")]
+fn std_ops_division_by_zero() {
+    let _ = FixedPoint::ONE / FixedPoint::ZERO;
+}
+
+#[test]
+#[cfg(all(
+    feature = "serde",
+    not(any(feature = "serde-float", feature = "serde-repr"))
+))]
+fn serde_default() -> Result<()> {
+    let a = fp("-1.5")?;
+    assert_eq!(serde_json::to_string(&a)?, r#""-1.5""#);
+    assert_eq!(serde_json::from_str::<FixedPoint>(r#""-1.5""#)?, a);
+    assert_eq!(serde_json::from_str::<FixedPoint>("-1.5")?, a);
+    assert_eq!(serde_json::from_str::<FixedPoint>("-1")?, fp("-1")?);
+
+    let b = crate::FixedPoint128::<18>::from_mantissa(-1);
+    assert_eq!(serde_json::to_string(&b)?, r#""-0.000000000000000001""#);
+    assert_eq!(
+        serde_json::from_str::<crate::FixedPoint128<18>>("0.3")?.mantissa(),
+        300_000_000_000_000_000
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serde_with() -> Result<()> {
+    use ::serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Order {
+        #[serde(with = "crate::serde::str")]
+        price: FixedPoint,
+        #[serde(with = "crate::serde::float")]
+        volume: crate::FixedPoint32<4>,
+        #[serde(with = "crate::serde::repr")]
+        fee: crate::FixedPoint128<18>,
     }
 
-    t("0", 0.0);
-    t("1", 1.0);
-    t("1.5", 1.5);
+    let order = Order {
+        price: fp("1.5")?,
+        volume: crate::FixedPoint32::from_mantissa(-12_345),
+        fee: crate::FixedPoint128::from_mantissa(1_000_000_000_000_000_001),
+    };
+    let json = r#"{"price":"1.5","volume":-1.2345,"fee":1000000000000000001}"#;
+
+    assert_eq!(serde_json::to_string(&order)?, json);
+    assert_eq!(serde_json::from_str::<Order>(json)?, order);
+
+    // Any representation is accepted.
+    let json = r#"{"price":1.5,"volume":"-1.2345","fee":"1.000000000000000001"}"#;
+    assert_eq!(serde_json::from_str::<Order>(json)?, order);
+    let json = r#"{"price":"1.5","volume":-1.23449,"fee":1.0}"#;
+    let order = serde_json::from_str::<Order>(json)?;
+    assert_eq!(order.volume, crate::FixedPoint32::from_mantissa(-12_345));
+    assert_eq!(order.fee, crate::FixedPoint128::ONE);
+
+    // Validation.
+    let bad = [
+        r#"{"price":"1.5000000001","volume":0,"fee":0}"#,
+        r#"{"price":"abc","volume":0,"fee":0}"#,
+        r#"{"price":10000000000,"volume":0,"fee":0}"#,
+        r#"{"price":1e20,"volume":0,"fee":0}"#,
+        r#"{"price":0,"volume":214749,"fee":0}"#,
+        r#"{"price":0,"volume":0,"fee":true}"#,
+    ];
+
+    for json in &bad {
+        assert!(serde_json::from_str::<Order>(json).is_err(), "{}", json);
+    }
+
+    Ok(())
+}
+
+macro_rules! assert_rmul {
+    ($a:expr, $b:expr, $mode:ident, $result:expr) => {{
+        let a = FixedPoint::try_from($a)?;
+        let b = FixedPoint::try_from($b)?;
+
+        // Check the commutative property.
+        assert_eq!(a.rmul(b, RoundMode::$mode), b.rmul(a, RoundMode::$mode));
+        // Check the result.
+        assert_eq!(
+            a.rmul(b, RoundMode::$mode),
+            Ok(FixedPoint::try_from($result)?)
+        );
+    }};
+}
+
+// TODO(hrls): remove
+macro_rules! assert_rmuls {
+    ($a:expr, $b:expr, $mode:ident, $result:expr) => {{
+        let a = fp(&format!("{}", $a))?;
+        let b = fp(&format!("{}", $b))?;
+
+        // Check the commutative property.
+        assert_eq!(a.rmul(b, RoundMode::$mode), b.rmul(a, RoundMode::$mode));
+        // Check the result.
+        assert_eq!(
+            a.rmul(b, RoundMode::$mode),
+            Ok(fp(&format!("{}", $result))?)
+        );
+    }};
+}
+
+#[test]
+#[allow(clippy::cognitive_complexity)]
+fn rmul_exact() -> Result<()> {
+    assert_rmul!(525, 10, Ceil, 5250);
+    assert_rmul!(525, 10, Floor, 5250);
+    assert_rmul!(-525, 10, Ceil, -5250);
+    assert_rmul!(-525, 10, Floor, -5250);
+    assert_rmul!(-525, -10, Ceil, 5250);
+    assert_rmul!(-525, -10, Floor, 5250);
+    assert_rmul!(525, -10, Ceil, -5250);
+    assert_rmul!(525, -10, Floor, -5250);
+    assert_rmuls!(525, "0.0001", Ceil, "0.0525");
+    assert_rmuls!(525, "0.0001", Floor, "0.0525");
+    assert_rmuls!(-525, "0.0001", Ceil, "-0.0525");
+    assert_rmuls!(-525, "0.0001", Floor, "-0.0525");
+    assert_rmuls!(-525, "-0.0001", Ceil, "0.0525");
+    assert_rmuls!(-525, "-0.0001", Floor, "0.0525");
+    assert_rmul!(FixedPoint::MAX, 1, Ceil, FixedPoint::MAX);
+    assert_rmul!(FixedPoint::MAX, 1, Floor, FixedPoint::MAX);
+    assert_rmuls!(
+        FixedPoint::from_mantissa(i64::MAX / 10 * 10),
+        "0.1",
+        Ceil,
+        FixedPoint::from_mantissa(i64::MAX / 10)
+    );
+    assert_rmuls!(
+        FixedPoint::from_mantissa(i64::MAX / 10 * 10),
+        "0.1",
+        Floor,
+        FixedPoint::from_mantissa(i64::MAX / 10)
+    );
+    assert_rmuls!(1, "0.000000001", Ceil, "0.000000001");
+    assert_rmuls!(1, "0.000000001", Floor, "0.000000001");
+    assert_rmuls!(-1, "-0.000000001", Ceil, "0.000000001");
+    assert_rmuls!(-1, "-0.000000001", Floor, "0.000000001");
+
+    Ok(())
+}
+
+#[test]
+fn rmul_round() -> Result<()> {
+    assert_rmuls!("0.1", "0.000000001", Ceil, "0.000000001");
+    assert_rmuls!("0.1", "0.000000001", Floor, 0);
+    assert_rmuls!("-0.1", "0.000000001", Ceil, 0);
+    assert_rmuls!("-0.1", "0.000000001", Floor, "-0.000000001");
+    assert_rmuls!("-0.1", "-0.000000001", Ceil, "0.000000001");
+    assert_rmuls!("-0.1", "-0.000000001", Floor, 0);
+    assert_rmuls!("0.000000001", "0.000000001", Ceil, "0.000000001");
+    assert_rmuls!("0.000000001", "0.000000001", Floor, 0);
+    assert_rmuls!("-0.000000001", "0.000000001", Ceil, 0);
+    assert_rmuls!("-0.000000001", "0.000000001", Floor, "-0.000000001");
+
+    Ok(())
+}
+
+#[test]
+fn rmul_overflow() -> Result<()> {
+    let a = FixedPoint::MAX;
+    let b = fp("1.1")?;
+    assert_eq!(a.rmul(b, RoundMode::Ceil), Err(ArithmeticError::Overflow));
+
+    let a = fp("140000")?;
+    assert_eq!(a.rmul(a, RoundMode::Ceil), Err(ArithmeticError::Overflow));
+
+    let a = fp("-140000")?;
+    let b = fp("140000")?;
+    assert_eq!(a.rmul(b, RoundMode::Ceil), Err(ArithmeticError::Overflow));
+
+    Ok(())
+}
+
+#[test]
+fn rdiv_exact() -> Result<()> {
+    let (numer, denom) = (fp("5")?, fp("2")?);
+    let result = fp("2.5")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+
+    let (numer, denom) = (fp("-5")?, fp("2")?);
+    let result = fp("-2.5")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+
+    let (numer, denom) = (fp("-5")?, fp("-2")?);
+    let result = fp("2.5")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+
+    let (numer, denom) = (fp("5")?, fp("-2")?);
+    let result = fp("-2.5")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+
+    let (numer, denom) = (FixedPoint::MAX, FixedPoint::MAX);
+    let result = fp("1")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+
+    let (numer, denom) = (fp("5")?, fp("0.2")?);
+    let result = fp("25")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+
+    let (numer, denom) = (fp("0.00000001")?, fp("10")?);
+    let result = fp("0.000000001")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+
+    let (numer, denom) = (fp("0.00000001")?, fp("0.1")?);
+    let result = fp("0.0000001")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(result));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(result));
+
+    Ok(())
+}
+
+#[test]
+fn rdiv_i64() -> Result<()> {
+    fn assert_rdiv(a: &str, b: i64, mode: RoundMode, expected: &str) -> Result<()> {
+        let a = fp(a)?;
+        let expected = fp(expected)?;
+        assert_eq!(a.rdiv(b, mode).unwrap(), expected);
+        Ok(())
+    }
+
+    assert_rdiv("2.4", 2, RoundMode::Ceil, "1.2")?;
+    assert_rdiv("7", 3, RoundMode::Floor, "2.333333333")?;
+    assert_rdiv("7", 3, RoundMode::Ceil, "2.333333334")?;
+    assert_rdiv("-7", 3, RoundMode::Floor, "-2.333333334")?;
+    assert_rdiv("-7", 3, RoundMode::Ceil, "-2.333333333")?;
+    assert_rdiv("-7", -3, RoundMode::Floor, "2.333333333")?;
+    assert_rdiv("-7", -3, RoundMode::Ceil, "2.333333334")?;
+    assert_rdiv("7", -3, RoundMode::Floor, "-2.333333334")?;
+    assert_rdiv("7", -3, RoundMode::Ceil, "-2.333333333")?;
+    assert_rdiv("0", 5, RoundMode::Ceil, "0")?;
+    assert_rdiv("0.000000003", 2, RoundMode::Ceil, "0.000000002")?;
+    assert_rdiv("0.000000003", 2, RoundMode::Floor, "0.000000001")?;
+    assert_rdiv("0.000000003", 7, RoundMode::Floor, "0")?;
+    assert_rdiv("0.000000003", 7, RoundMode::Ceil, "0.000000001")?;
+    assert_rdiv("0.000000001", 7, RoundMode::Ceil, "0.000000001")?;
+    Ok(())
+}
+
+#[test]
+fn rdiv_round() -> Result<()> {
+    let (numer, denom) = (fp("100")?, fp("3")?);
+    let ceil = fp("33.333333334")?;
+    let floor = fp("33.333333333")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(ceil));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(floor));
+
+    let (numer, denom) = (fp("-100")?, fp("3")?);
+    let ceil = fp("-33.333333333")?;
+    let floor = fp("-33.333333334")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(ceil));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(floor));
+
+    let (numer, denom) = (fp("-100")?, fp("-3")?);
+    let ceil = fp("33.333333334")?;
+    let floor = fp("33.333333333")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(ceil));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(floor));
+
+    let (numer, denom) = (fp("100")?, fp("-3")?);
+    let ceil = fp("-33.333333333")?;
+    let floor = fp("-33.333333334")?;
+    assert_eq!(numer.rdiv(denom, RoundMode::Ceil), Ok(ceil));
+    assert_eq!(numer.rdiv(denom, RoundMode::Floor), Ok(floor));
+
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::cognitive_complexity)]
+fn round_half() -> Result<()> {
+    use RoundMode::{HalfDown, HalfEven, HalfUp};
+
+    // Exactly a half.
+    assert_rmuls!("0.5", "0.000000001", HalfUp, "0.000000001");
+    assert_rmuls!("0.5", "0.000000001", HalfDown, 0);
+    assert_rmuls!("0.5", "0.000000001", HalfEven, 0);
+    assert_rmuls!("1.5", "0.000000001", HalfEven, "0.000000002");
+    assert_rmuls!("-0.5", "0.000000001", HalfUp, "-0.000000001");
+    assert_rmuls!("-0.5", "0.000000001", HalfDown, 0);
+    assert_rmuls!("-1.5", "0.000000001", HalfEven, "-0.000000002");
+    assert_rmuls!("-2.5", "0.000000001", HalfEven, "-0.000000002");
+
+    // Not a half.
+    assert_rmuls!("0.4", "0.000000001", HalfUp, 0);
+    assert_rmuls!("0.6", "0.000000001", HalfDown, "0.000000001");
+    assert_rmuls!("-0.6", "0.000000001", HalfEven, "-0.000000001");
+    assert_rmuls!("-0.4", "0.000000001", HalfEven, 0);
+
+    let (numer, denom) = (fp("2")?, fp("3")?);
+    assert_eq!(numer.rdiv(denom, HalfUp), Ok(fp("0.666666667")?));
+    assert_eq!(numer.rdiv(denom, HalfDown), Ok(fp("0.666666667")?));
+    assert_eq!(numer.cneg()?.rdiv(denom, HalfEven), Ok(fp("-0.666666667")?));
+    assert_eq!(numer.rdiv(denom.cneg()?, HalfDown), Ok(fp("-0.666666667")?));
+
+    let (numer, denom) = (fp("0.000000005")?, fp("2")?);
+    assert_eq!(numer.rdiv(denom, HalfUp), Ok(fp("0.000000003")?));
+    assert_eq!(numer.rdiv(denom, HalfDown), Ok(fp("0.000000002")?));
+    assert_eq!(numer.rdiv(denom, HalfEven), Ok(fp("0.000000002")?));
+    assert_eq!(numer.rdiv(denom.cneg()?, HalfUp), Ok(fp("-0.000000003")?));
+    assert_eq!(numer.rdiv(denom.cneg()?, HalfEven), Ok(fp("-0.000000002")?));
+
+    assert_eq!(numer.rdiv(2, HalfUp), Ok(fp("0.000000003")?));
+    assert_eq!(numer.rdiv(2, HalfDown), Ok(fp("0.000000002")?));
+    assert_eq!(numer.rdiv(-2, HalfEven), Ok(fp("-0.000000002")?));
+    assert_eq!(fp("0.000000007")?.rdiv(2, HalfEven), Ok(fp("0.000000004")?));
+    assert_eq!(fp("0.000000007")?.rdiv(3, HalfDown), Ok(fp("0.000000002")?));
+    assert_eq!(
+        fp("0.000000008")?.rdiv(-3, HalfDown),
+        Ok(fp("-0.000000003")?)
+    );
+    assert_eq!(
+        FixedPoint::MAX.rdiv(i64::MIN, HalfUp),
+        Ok(FixedPoint::from_mantissa(-1))
+    );
+    assert_eq!(
+        FixedPoint::MIN.rdiv(-1, HalfUp),
+        Err(ArithmeticError::Overflow)
+    );
+
+    assert_eq!(fp("3")?.recip(HalfUp), Ok(fp("0.333333333")?));
+    assert_eq!(fp("-6")?.recip(HalfUp), Ok(fp("-0.166666667")?));
+    assert_eq!(fp("-6")?.recip(HalfDown), Ok(fp("-0.166666667")?));
+
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::cognitive_complexity)]
+fn round_toward_and_away_from_zero() -> Result<()> {
+    use RoundMode::{AwayFromZero, TowardZero};
+
+    assert_rmuls!("0.1", "0.000000001", TowardZero, 0);
+    assert_rmuls!("0.1", "0.000000001", AwayFromZero, "0.000000001");
+    assert_rmuls!("-0.1", "0.000000001", TowardZero, 0);
+    assert_rmuls!("-0.1", "0.000000001", AwayFromZero, "-0.000000001");
+    assert_rmuls!("-0.9", "-0.000000001", TowardZero, 0);
+    assert_rmuls!("-0.9", "-0.000000001", AwayFromZero, "0.000000001");
+
+    let (numer, denom) = (fp("100")?, fp("3")?);
+    assert_eq!(numer.rdiv(denom, TowardZero), Ok(fp("33.333333333")?));
+    assert_eq!(numer.rdiv(denom, AwayFromZero), Ok(fp("33.333333334")?));
+    assert_eq!(
+        numer.rdiv(denom.cneg()?, TowardZero),
+        Ok(fp("-33.333333333")?)
+    );
+    assert_eq!(
+        numer.rdiv(denom.cneg()?, AwayFromZero),
+        Ok(fp("-33.333333334")?)
+    );
+    assert_eq!(fp("-7")?.rdiv(3, TowardZero), Ok(fp("-2.333333333")?));
+    assert_eq!(fp("-7")?.rdiv(3, AwayFromZero), Ok(fp("-2.333333334")?));
+    assert_eq!(fp("-7")?.rdiv(-3, AwayFromZero), Ok(fp("2.333333334")?));
+
+    assert_eq!(fp("-2.0001")?.integral(TowardZero), Ok(-2));
+    assert_eq!(fp("-2.0001")?.integral(AwayFromZero), Ok(-3));
+    assert_eq!(fp("2.9999")?.integral(TowardZero), Ok(2));
+    assert_eq!(fp("2.0001")?.integral(AwayFromZero), Ok(3));
+    assert_eq!(
+        FixedPoint::half_sum(fp("-0.000000001")?, FixedPoint::ZERO, TowardZero),
+        Ok(FixedPoint::ZERO)
+    );
+    assert_eq!(
+        FixedPoint::half_sum(fp("-0.000000001")?, FixedPoint::ZERO, AwayFromZero),
+        Ok(fp("-0.000000001")?)
+    );
+    assert_eq!(
+        FixedPoint::rounding_from_f64(-0.000_000_000_1, AwayFromZero),
+        Ok(fp("-0.000000001")?)
+    );
+    assert_eq!(
+        FixedPoint::rounding_from_f64(-0.000_000_000_9, TowardZero),
+        Ok(FixedPoint::ZERO)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn round_unnecessary() -> Result<()> {
+    use RoundMode::Unnecessary;
+
+    assert_rmuls!(525, "0.0001", Unnecessary, "0.0525");
+    assert_rmuls!("-0.1", "0.00000001", Unnecessary, "-0.000000001");
+    assert_eq!(
+        fp("0.1")?.rmul(fp("0.000000001")?, Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+    assert_eq!(
+        FixedPoint::MAX.rmul(fp("2")?, Unnecessary),
+        Err(ArithmeticError::Overflow)
+    );
+
+    assert_eq!(fp("5")?.rdiv(fp("-2")?, Unnecessary), Ok(fp("-2.5")?));
+    assert_eq!(
+        fp("1")?.rdiv(fp("3")?, Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+    assert_eq!(
+        fp("1")?.rdiv(FixedPoint::ZERO, Unnecessary),
+        Err(ArithmeticError::DivisionByZero)
+    );
+    assert_eq!(fp("-7")?.rdiv(7, Unnecessary), Ok(fp("-1")?));
+    assert_eq!(fp("7")?.rdiv(3, Unnecessary), Err(ArithmeticError::Inexact));
+    assert_eq!(fp("4")?.recip(Unnecessary), Ok(fp("0.25")?));
+    assert_eq!(fp("3")?.recip(Unnecessary), Err(ArithmeticError::Inexact));
+
+    assert_eq!(fp("-42")?.integral(Unnecessary), Ok(-42));
+    assert_eq!(
+        fp("42.5")?.integral(Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+    assert_eq!(
+        FixedPoint::half_sum(fp("1")?, fp("2")?, Unnecessary),
+        Ok(fp("1.5")?)
+    );
+    assert_eq!(
+        FixedPoint::half_sum(fp("1")?, fp("0.000000002")?, Unnecessary),
+        Ok(fp("0.500000001")?)
+    );
+    assert_eq!(
+        FixedPoint::half_sum(fp("1")?, fp("0.000000001")?, Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+    assert_eq!(
+        FixedPoint::rounding_from_f64(0.25, Unnecessary),
+        Ok(fp("0.25")?)
+    );
+    assert_eq!(
+        FixedPoint::rounding_from_f64(0.000_000_000_5, Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+
+    type Wei = crate::FixedPoint128<18>;
+    let third = Wei::ONE.rdiv(Wei::from(3), RoundMode::Floor)?;
+    assert_eq!(
+        third.rmul(Wei::from(3), Unnecessary),
+        Ok(Wei::from_mantissa(999_999_999_999_999_999))
+    );
+    assert_eq!(
+        third.rmul(third, Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn rounding_from_f64() -> Result<()> {
+    fn t(x: f64, mode: RoundMode, expected: &str) -> Result<()> {
+        let expected = fp(expected)?;
+        assert_eq!(FixedPoint::rounding_from_f64(x, mode)?, expected);
+        Ok(())
+    }
+
+    t(0.5, RoundMode::Ceil, "0.5")?;
+    t(0.000_000_000_4, RoundMode::Ceil, "0.000000001")?;
+    t(0.000_000_000_4, RoundMode::Floor, "0")?;
+    t(-0.000_000_000_4, RoundMode::Floor, "-0.000000001")?;
+    t(0.000_000_000_4, RoundMode::HalfUp, "0")?;
+    t(0.000_000_000_6, RoundMode::HalfDown, "0.000000001")?;
+    t(0.000_000_002_5, RoundMode::HalfUp, "0.000000003")?;
+    t(0.000_000_002_5, RoundMode::HalfDown, "0.000000002")?;
+    t(-0.000_000_002_5, RoundMode::HalfDown, "-0.000000002")?;
+    t(0.000_000_002_5, RoundMode::HalfEven, "0.000000002")?;
+    t(0.000_000_003_5, RoundMode::HalfEven, "0.000000004")?;
+    t(-0.000_000_003_5, RoundMode::AwayFromZero, "-0.000000004")?;
+    t(9e9, RoundMode::Unnecessary, "9000000000")?;
+
+    assert_eq!(
+        FixedPoint::rounding_from_f64(1e11, RoundMode::Floor),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(
+        FixedPoint::rounding_from_f64(f64::NAN, RoundMode::Floor),
+        Err(ArithmeticError::Overflow)
+    );
+
+    // `i64::MAX` is rounded up to 2^63 in `f64`.
+    type Integer = crate::FixedPoint<0>;
+    assert_eq!(
+        Integer::rounding_from_f64(i64::MAX as f64, RoundMode::Floor),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(
+        Integer::rounding_from_f64(i64::MIN as f64, RoundMode::Floor)?,
+        Integer::MIN
+    );
+
+    Ok(())
+}
+
+#[test]
+fn from_f64() -> Result<()> {
+    fn t(x: f64, mode: RoundMode, expected: &str) -> Result<()> {
+        let expected = fp(expected)?;
+        assert_eq!(FixedPoint::from_f64(x, mode)?, expected, "{} {:?}", x, mode);
+        Ok(())
+    }
+
+    // 2.5e-9 is slightly above the half, 3.5e-9 is slightly below.
+    t(0.000_000_002_5, RoundMode::HalfEven, "0.000000003")?;
+    t(-0.000_000_002_5, RoundMode::HalfDown, "-0.000000003")?;
+    t(0.000_000_003_5, RoundMode::HalfEven, "0.000000003")?;
+    t(0.5, RoundMode::Unnecessary, "0.5")?;
+    t(-1.25, RoundMode::Unnecessary, "-1.25")?;
+    t(0.1, RoundMode::Floor, "0.1")?;
+    t(0.1, RoundMode::Ceil, "0.100000001")?;
+    t(1e-300, RoundMode::Ceil, "0.000000001")?;
+    t(1e-300, RoundMode::HalfUp, "0")?;
+    t(-5e-324, RoundMode::Floor, "-0.000000001")?;
+    t(-0.0, RoundMode::Unnecessary, "0")?;
+    t(
+        9_223_372_036.854_775,
+        RoundMode::Floor,
+        "9223372036.854774475",
+    )?;
+
+    assert_eq!(
+        FixedPoint::from_f64(0.1, RoundMode::Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+    assert_eq!(
+        FixedPoint::from_f64(1e-300, RoundMode::Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+    assert_eq!(FixedPoint::try_from_f64_exact(0.375)?, fp("0.375")?);
+    assert_eq!(
+        FixedPoint::try_from_f64_exact(0.3),
+        Err(ArithmeticError::Inexact)
+    );
+
+    for &x in &[
+        1e10,
+        -1e10,
+        1e300,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NAN,
+    ] {
+        assert_eq!(
+            FixedPoint::from_f64(x, RoundMode::Floor),
+            Err(ArithmeticError::Overflow)
+        );
+    }
+
+    // Integers near the layout's bounds.
+    type Int = crate::FixedPoint<0>;
+    assert_eq!(Int::try_from_f64_exact(i64::MIN as f64)?, Int::MIN);
+    assert_eq!(
+        Int::try_from_f64_exact(-(i64::MIN as f64)),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(
+        Int::try_from_f64_exact(4_611_686_018_427_387_904.)?.mantissa(),
+        1 << 62
+    );
+
+    type Wei = crate::FixedPoint128<18>;
+    assert_eq!(
+        Wei::from_f64(0.1, RoundMode::Floor)?.mantissa(),
+        100_000_000_000_000_005
+    );
+    assert_eq!(
+        crate::FixedPoint128::<0>::try_from_f64_exact(i128::MIN as f64)?,
+        crate::FixedPoint128::MIN
+    );
+
+    assert_eq!(
+        FixedPoint::from_f32(0.1, RoundMode::HalfEven)?,
+        fp("0.100000001")?
+    );
+    assert_eq!(FixedPoint::try_from_f32_exact(-0.75)?, fp("-0.75")?);
+    assert_eq!(
+        crate::FixedPoint32::<9>::from_f32(3., RoundMode::Floor),
+        Err(ArithmeticError::Overflow)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn from_f64_shortest() -> Result<()> {
+    fn t(x: f64, mode: RoundMode, expected: &str) -> Result<()> {
+        let expected = fp(expected)?;
+        assert_eq!(
+            FixedPoint::from_f64_shortest(x, mode)?,
+            expected,
+            "{} {:?}",
+            x,
+            mode
+        );
+        Ok(())
+    }
+
+    t(0.3, RoundMode::Unnecessary, "0.3")?;
+    t(-0.3, RoundMode::Unnecessary, "-0.3")?;
+    t(0.1 + 0.2, RoundMode::HalfEven, "0.3")?;
+    t(0.1 + 0.2, RoundMode::Ceil, "0.300000001")?;
+    t(1e-10, RoundMode::HalfEven, "0")?;
+    t(1e-10, RoundMode::Ceil, "0.000000001")?;
+    t(-1e-300, RoundMode::Floor, "-0.000000001")?;
+    t(5e-324, RoundMode::Floor, "0")?;
+    t(-0.0, RoundMode::Unnecessary, "0")?;
+    t(1.5e9, RoundMode::Unnecessary, "1500000000")?;
+    t(
+        123_456_789.123_456_79,
+        RoundMode::Unnecessary,
+        "123456789.12345679",
+    )?;
+
+    // Ties are decided by the decimal representation, not by the binary value.
+    t(0.000_000_002_5, RoundMode::HalfEven, "0.000000002")?;
+    t(-0.000_000_002_5, RoundMode::HalfDown, "-0.000000002")?;
+    t(0.000_000_002_5, RoundMode::HalfUp, "0.000000003")?;
+
+    assert_eq!(
+        FixedPoint::from_f64_shortest(0.1 + 0.2, RoundMode::Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+
+    for &x in &[1e10, -1e10, f64::MAX, f64::INFINITY, f64::NAN] {
+        assert_eq!(
+            FixedPoint::from_f64_shortest(x, RoundMode::Floor),
+            Err(ArithmeticError::Overflow)
+        );
+    }
+
+    type Wei = crate::FixedPoint128<18>;
+    assert_eq!(
+        Wei::from_f64_shortest(0.1, RoundMode::Unnecessary)?.mantissa(),
+        100_000_000_000_000_000
+    );
+    assert_eq!(
+        Wei::from_f64_shortest(1e20, RoundMode::Unnecessary)?.mantissa(),
+        100_000_000_000_000_000_000_000_000_000_000_000_000
+    );
+    assert_eq!(
+        crate::FixedPoint32::<9>::from_f64_shortest(2.147_483_647, RoundMode::Unnecessary)?,
+        crate::FixedPoint32::MAX
+    );
+
+    Ok(())
+}
+
+#[test]
+fn to_float_is_correctly_rounded() {
+    // `str::parse` is correctly rounded.
+    let mut mantissa = 1i64;
+    while mantissa < i64::MAX / 7 {
+        for &m in &[mantissa, -mantissa] {
+            let x = crate::FixedPoint::<18>::from_mantissa(m);
+            let repr = format!("{}e-18", m);
+            assert_eq!(x.to_f64(), repr.parse::<f64>().unwrap(), "{}", repr);
+            assert_eq!(x.to_f32(), repr.parse::<f32>().unwrap(), "{}", repr);
+        }
+        mantissa = mantissa * 7 + 3;
+    }
+
+    assert_eq!(FixedPoint::MIN.to_f64(), -9_223_372_036.854_776);
+    assert_eq!(crate::FixedPoint128::<0>::MAX.to_f32(), 1.701_411_8e38);
+
+    // Subnormal `f32` values.
+    let tiny = crate::FixedPoint128::<38>::from_mantissa(7);
+    assert_eq!(tiny.to_f32(), 7e-38);
+    assert_eq!(tiny.to_f64(), 7e-38);
+    assert_eq!(crate::FixedPoint128::<38>::EPSILON.to_f32(), 1e-38);
+}
+
+#[test]
+fn rdiv_division_by_zero() -> Result<()> {
+    assert_eq!(
+        FixedPoint::MAX.rdiv(FixedPoint::ZERO, RoundMode::Ceil),
+        Err(ArithmeticError::DivisionByZero)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn rdiv_overflow() -> Result<()> {
+    assert_eq!(
+        FixedPoint::MAX.rdiv(fp("0.5")?, RoundMode::Ceil),
+        Err(ArithmeticError::Overflow)
+    );
+    Ok(())
+}
+
+#[test]
+fn float_mul() {
+    let a = FixedPoint::from(525);
+    let b = FixedPoint::from(10);
+    assert_eq!(a.rmul(b, Ceil), Ok(FixedPoint::from(5250)));
+
+    let a = FixedPoint::from(525);
+    let b = FixedPoint::from_str("0.0001").unwrap();
+    assert_eq!(a.rmul(b, Ceil), Ok(FixedPoint::from_str("0.0525").unwrap()));
+
+    let a = FixedPoint::MAX;
+    let b = FixedPoint::from(1);
+    assert_eq!(a.rmul(b, Ceil), Ok(FixedPoint::MAX));
+
+    let a = FixedPoint::from_mantissa(i64::MAX / 10 * 10);
+    let b = FixedPoint::from_str("0.1").unwrap();
+    assert_eq!(
+        a.rmul(b, Ceil),
+        Ok(FixedPoint::from_mantissa(i64::MAX / 10))
+    );
+}
+
+#[test]
+fn float_mul_overflow() {
+    let a = FixedPoint::from(140_000);
+    assert!(a.rmul(a, Ceil).is_err());
+
+    let a = FixedPoint::from(-140_000);
+    let b = FixedPoint::from(140_000);
+    assert!(a.rmul(b, Ceil).is_err());
+}
+
+#[test]
+fn half_sum() -> Result<()> {
+    fn t(a: &str, b: &str, r: &str) -> Result<()> {
+        let a = fp(a)?;
+        let b = fp(b)?;
+        let r = fp(r)?;
+        assert_eq!(FixedPoint::half_sum(a, b, RoundMode::Floor)?, r);
+        Ok(())
+    }
+
+    t("1", "3", "2")?;
+    t("1", "2", "1.5")?;
+    t("9000", "9050", "9025")?;
+    t("9000", "-9000", "0")?;
+    t("9000000000", "9000000002", "9000000001")?;
+    t(
+        "9000000000.000000001",
+        "-9000000000.000000005",
+        "-0.000000002",
+    )?;
+    t("7.123456789", "7.123456788", "7.123456788")?;
+
+    Ok(())
+}
+
+#[test]
+fn half_sum_round() -> Result<()> {
+    fn t(a: &str, b: &str, mode: RoundMode, r: &str) -> Result<()> {
+        let a = fp(a)?;
+        let b = fp(b)?;
+        assert_eq!(FixedPoint::half_sum(a, b, mode)?, fp(r)?);
+        assert_eq!(FixedPoint::half_sum(b, a, mode)?, fp(r)?);
+        Ok(())
+    }
+
+    t(
+        "0.000000001",
+        "0.000000002",
+        RoundMode::Floor,
+        "0.000000001",
+    )?;
+    t("0.000000001", "0.000000002", RoundMode::Ceil, "0.000000002")?;
+    t(
+        "0.000000001",
+        "0.000000002",
+        RoundMode::HalfUp,
+        "0.000000002",
+    )?;
+    t(
+        "0.000000001",
+        "0.000000002",
+        RoundMode::HalfDown,
+        "0.000000001",
+    )?;
+    t(
+        "0.000000001",
+        "0.000000002",
+        RoundMode::HalfEven,
+        "0.000000002",
+    )?;
+    t(
+        "0.000000001",
+        "0.000000004",
+        RoundMode::HalfEven,
+        "0.000000002",
+    )?;
+    t(
+        "-0.000000001",
+        "-0.000000002",
+        RoundMode::Floor,
+        "-0.000000002",
+    )?;
+    t(
+        "-0.000000001",
+        "-0.000000002",
+        RoundMode::Ceil,
+        "-0.000000001",
+    )?;
+    t(
+        "-0.000000001",
+        "-0.000000002",
+        RoundMode::HalfUp,
+        "-0.000000002",
+    )?;
+    t(
+        "-0.000000001",
+        "-0.000000002",
+        RoundMode::HalfDown,
+        "-0.000000001",
+    )?;
+    t(
+        "-0.000000001",
+        "-0.000000004",
+        RoundMode::HalfEven,
+        "-0.000000002",
+    )?;
+    t(
+        "0.000000001",
+        "-0.000000002",
+        RoundMode::Floor,
+        "-0.000000001",
+    )?;
+    t("0.000000001", "-0.000000002", RoundMode::Ceil, "0")?;
+
+    assert_eq!(
+        FixedPoint::half_sum(FixedPoint::MAX, FixedPoint::MAX, RoundMode::Ceil)?,
+        FixedPoint::MAX
+    );
+    assert_eq!(
+        FixedPoint::half_sum(FixedPoint::MIN, FixedPoint::MAX, RoundMode::Floor)?,
+        FixedPoint::from_mantissa(-1)
+    );
+
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::many_single_char_names)]
+fn integral() -> Result<()> {
+    let a = fp("0.0001")?;
+    assert_eq!(a.integral(RoundMode::Floor), Ok(0));
+    assert_eq!(a.integral(RoundMode::Ceil), Ok(1));
+
+    let b = fp("-0.0001")?;
+    assert_eq!(b.integral(RoundMode::Floor), Ok(-1));
+    assert_eq!(b.integral(RoundMode::Ceil), Ok(0));
+
+    let c = FixedPoint::ZERO;
+    assert_eq!(c.integral(RoundMode::Floor), Ok(0));
+    assert_eq!(c.integral(RoundMode::Ceil), Ok(0));
+
+    let d = fp("2.0001")?;
+    assert_eq!(d.integral(RoundMode::Floor), Ok(2));
+    assert_eq!(d.integral(RoundMode::Ceil), Ok(3));
+
+    let e = fp("-2.0001")?;
+    assert_eq!(e.integral(RoundMode::Floor), Ok(-3));
+    assert_eq!(e.integral(RoundMode::Ceil), Ok(-2));
+
+    Ok(())
+}
+
+#[test]
+fn integral_half() -> Result<()> {
+    fn t(x: &str, up: i64, down: i64, even: i64) -> Result<()> {
+        let x = fp(x)?;
+        assert_eq!(x.integral(RoundMode::HalfUp), Ok(up), "{} HalfUp", x);
+        assert_eq!(x.integral(RoundMode::HalfDown), Ok(down), "{} HalfDown", x);
+        assert_eq!(x.integral(RoundMode::HalfEven), Ok(even), "{} HalfEven", x);
+        Ok(())
+    }
+
+    t("0", 0, 0, 0)?;
+    t("2.4999", 2, 2, 2)?;
+    t("2.5", 3, 2, 2)?;
+    t("2.5001", 3, 3, 3)?;
+    t("3.5", 4, 3, 4)?;
+    t("0.5", 1, 0, 0)?;
+    t("-0.5", -1, 0, 0)?;
+    t("-2.5", -3, -2, -2)?;
+    t("-3.5", -4, -3, -4)?;
+    t("-3.4999", -3, -3, -3)?;
+    t("-3.5001", -4, -4, -4)?;
+    assert_eq!(
+        FixedPoint::MIN.integral(RoundMode::HalfEven),
+        Ok(-9_223_372_037)
+    );
+    assert_eq!(
+        FixedPoint::MAX.integral(RoundMode::HalfDown),
+        Ok(9_223_372_037)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn round_towards_zero_by() -> Result<()> {
+    let a = fp("1234.56789")?;
+    assert_eq!(a.round_towards_zero_by(fp("100")?), fp("1200")?);
+    assert_eq!(a.round_towards_zero_by(fp("10")?), fp("1230")?);
+    assert_eq!(a.round_towards_zero_by(fp("1")?), fp("1234")?);
+    assert_eq!(a.round_towards_zero_by(fp("0.1")?), fp("1234.5")?);
+    assert_eq!(a.round_towards_zero_by(fp("0.01")?), fp("1234.56")?);
+    assert_eq!(a.round_towards_zero_by(fp("0.001")?), fp("1234.567")?);
+    assert_eq!(a.round_towards_zero_by(fp("0.0001")?), fp("1234.5678")?);
+    assert_eq!(a.round_towards_zero_by(fp("0.00001")?), fp("1234.56789")?);
+
+    let b = fp("-1234.56789")?;
+    assert_eq!(b.round_towards_zero_by(fp("100")?), fp("-1200")?);
+    assert_eq!(b.round_towards_zero_by(fp("10")?), fp("-1230")?);
+    assert_eq!(b.round_towards_zero_by(fp("1")?), fp("-1234")?);
+    assert_eq!(b.round_towards_zero_by(fp("0.1")?), fp("-1234.5")?);
+    assert_eq!(b.round_towards_zero_by(fp("0.01")?), fp("-1234.56")?);
+    assert_eq!(b.round_towards_zero_by(fp("0.001")?), fp("-1234.567")?);
+    assert_eq!(b.round_towards_zero_by(fp("0.0001")?), fp("-1234.5678")?);
+    assert_eq!(b.round_towards_zero_by(fp("0.00001")?), fp("-1234.56789")?);
+
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::cognitive_complexity)]
+fn next_power_of_ten() -> Result<()> {
+    assert_eq!(fp("0")?.next_power_of_ten()?, fp("0.000000001")?);
+    assert_eq!(fp("0.000000001")?.next_power_of_ten()?, fp("0.000000001")?);
+    assert_eq!(fp("0.000000002")?.next_power_of_ten()?, fp("0.00000001")?);
+    assert_eq!(fp("0.000000009")?.next_power_of_ten()?, fp("0.00000001")?);
+    assert_eq!(fp("0.00000001")?.next_power_of_ten()?, fp("0.00000001")?);
+    assert_eq!(fp("0.00000002")?.next_power_of_ten()?, fp("0.0000001")?);
+    assert_eq!(fp("0.1")?.next_power_of_ten()?, fp("0.1")?);
+    assert_eq!(fp("0.100000001")?.next_power_of_ten()?, fp("1")?);
+    assert_eq!(fp("1")?.next_power_of_ten()?, fp("1")?);
+    assert_eq!(fp("2")?.next_power_of_ten()?, fp("10")?);
+    assert_eq!(fp("1234567")?.next_power_of_ten()?, fp("10000000")?);
+    assert_eq!(
+        fp("923372036.854775807")?.next_power_of_ten()?,
+        fp("1000000000")?
+    );
+    assert_eq!(
+        fp("9223372036.854775807")?.next_power_of_ten(),
+        Err(ArithmeticError::Overflow)
+    );
+
+    assert_eq!(
+        fp("-0.000000001")?.next_power_of_ten()?,
+        fp("-0.000000001")?
+    );
+    assert_eq!(fp("-0.000000002")?.next_power_of_ten()?, fp("-0.00000001")?);
+    assert_eq!(fp("-0.000000009")?.next_power_of_ten()?, fp("-0.00000001")?);
+    assert_eq!(fp("-0.00000001")?.next_power_of_ten()?, fp("-0.00000001")?);
+    assert_eq!(fp("-0.00000002")?.next_power_of_ten()?, fp("-0.0000001")?);
+    assert_eq!(fp("-0.1")?.next_power_of_ten()?, fp("-0.1")?);
+    assert_eq!(fp("-0.2")?.next_power_of_ten()?, fp("-1")?);
+    assert_eq!(fp("-1")?.next_power_of_ten()?, fp("-1")?);
+    assert_eq!(fp("-0.100000001")?.next_power_of_ten()?, fp("-1")?);
+    assert_eq!(fp("-1234567")?.next_power_of_ten()?, fp("-10000000")?);
+    assert_eq!(
+        fp("-923372036.854775808")?.next_power_of_ten()?,
+        fp("-1000000000")?
+    );
+    assert_eq!(
+        fp("-9223372036.854775807")?.next_power_of_ten(),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(
+        fp("-9223372036.854775808")?.next_power_of_ten(),
+        Err(ArithmeticError::Overflow)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn rounding_to_i64() {
+    fn t(x: &str, r: i64) {
+        let f = FixedPoint::from_str(x).unwrap();
+        assert_eq!(f.rounding_to_i64(), r);
+    }
+
+    t("0", 0);
+    t("42", 42);
+    t("1.4", 1);
+    t("1.6", 2);
+    t("-1.4", -1);
+    t("-1.6", -2);
+    t("0.4999", 0);
+    t("0.5", 1);
+    t("0.5001", 1);
+    t("-0.5", -1);
+
+    assert_eq!(FixedPoint::MAX.rounding_to_i64(), 9_223_372_037);
+    assert_eq!(FixedPoint::MIN.rounding_to_i64(), -9_223_372_037);
+    assert_eq!(crate::FixedPoint::<0>::MAX.rounding_to_i64(), i64::MAX);
+    assert_eq!(crate::FixedPoint32::<9>::MIN.rounding_to_i32(), -2);
+}
+
+#[test]
+fn to_f64() {
+    fn t(x: &str, expected: f64) {
+        let f = FixedPoint::from_str(x).unwrap();
+        let actual = f.to_f64();
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    t("0", 0.0);
+    t("1", 1.0);
+    t("1.5", 1.5);
     t("42.123456789", 42.123_456_789);
     t("-14.14", -14.14);
-    t("8003332421.536753168", 8_003_332_421.536_754);
+    t("8003332421.536753168", 8_003_332_421.536_753);
+}
+
+#[test]
+fn rsqrt() -> Result<()> {
+    fn t(x: &str, mode: RoundMode, expected: &str) -> Result<()> {
+        assert_eq!(fp(x)?.rsqrt(mode)?, fp(expected)?, "sqrt({}) {:?}", x, mode);
+        Ok(())
+    }
+
+    t("0", RoundMode::Unnecessary, "0")?;
+    t("1", RoundMode::Unnecessary, "1")?;
+    t("2.25", RoundMode::Unnecessary, "1.5")?;
+    t("0.000000001", RoundMode::Floor, "0.000031622")?;
+    t("0.000000001", RoundMode::Ceil, "0.000031623")?;
+    t("2", RoundMode::Floor, "1.414213562")?;
+    t("2", RoundMode::Ceil, "1.414213563")?;
+    t("2", RoundMode::HalfDown, "1.414213562")?;
+    t("3", RoundMode::HalfUp, "1.732050808")?;
+    t("3", RoundMode::TowardZero, "1.732050807")?;
+    t("3", RoundMode::AwayFromZero, "1.732050808")?;
+    t(
+        "9223372036.854775807",
+        RoundMode::HalfEven,
+        "96038.388349945",
+    )?;
+
+    assert_eq!(
+        fp("2")?.rsqrt(RoundMode::Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+    assert_eq!(
+        fp("-0.000000001")?.rsqrt(RoundMode::Floor),
+        Err(ArithmeticError::DomainViolation)
+    );
+
+    // The floor root `r` of `m * COEF` must satisfy `r^2 <= m * COEF < (r + 1)^2`.
+    let mut mantissa = 1i64;
+    while mantissa < i64::MAX / 7 {
+        let r = FixedPoint::from_mantissa(mantissa)
+            .rsqrt(RoundMode::Floor)?
+            .mantissa();
+        let square = i128::from(mantissa) * 1_000_000_000;
+        assert!(i128::from(r).pow(2) <= square && i128::from(r + 1).pow(2) > square);
+        mantissa = mantissa * 7 + 3;
+    }
+
+    let small = crate::FixedPoint32::<4>::from_mantissa(20_000);
+    assert_eq!(
+        small.rsqrt(RoundMode::HalfEven)?,
+        crate::FixedPoint32::from_mantissa(14_142)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn rpow() -> Result<()> {
+    fn t(x: &str, exp: i32, mode: RoundMode, expected: &str) -> Result<()> {
+        let (x, expected) = (fp(x)?, fp(expected)?);
+        assert_eq!(x.rpow(exp, mode)?, expected, "{}^{} {:?}", x, exp, mode);
+        assert_eq!(
+            x.rpow_precise(exp, mode)?,
+            expected,
+            "{}^{} {:?}",
+            x,
+            exp,
+            mode
+        );
+        Ok(())
+    }
+
+    t("0", 0, RoundMode::Unnecessary, "1")?;
+    t("7.5", 1, RoundMode::Unnecessary, "7.5")?;
+    t("-1.5", 7, RoundMode::Unnecessary, "-17.0859375")?;
+    t("2", -3, RoundMode::Unnecessary, "0.125")?;
+    t("0.1", 9, RoundMode::Unnecessary, "0.000000001")?;
+    t("0.1", 10, RoundMode::Floor, "0")?;
+    t("0.1", 10, RoundMode::Ceil, "0.000000001")?;
+    t("3", 20, RoundMode::Unnecessary, "3486784401")?;
+
+    // Intermediate rounding accumulates, guard digits don't let it reach the result.
+    let growth = fp("1.05")?;
+    assert_eq!(
+        growth.rpow_precise(252, RoundMode::HalfEven)?,
+        fp("218626.783632220")?
+    );
+    assert_ne!(
+        growth.rpow(252, RoundMode::HalfEven)?,
+        fp("218626.783632220")?
+    );
+    assert_eq!(
+        fp("1.0001")?.rpow_precise(-10_000, RoundMode::HalfEven)?,
+        fp("0.367897834")?
+    );
+    assert_eq!(
+        fp("0.3")?.rpow_precise(-5, RoundMode::HalfEven)?,
+        fp("411.522633745")?
+    );
+
+    assert_eq!(
+        fp("2")?.rpow(34, RoundMode::Floor),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(
+        fp("2")?.rpow_precise(34, RoundMode::Floor),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(
+        fp("0")?.rpow(-1, RoundMode::Floor),
+        Err(ArithmeticError::DivisionByZero)
+    );
+    assert_eq!(
+        fp("0")?.rpow_precise(-1, RoundMode::Floor),
+        Err(ArithmeticError::DivisionByZero)
+    );
+
+    let small = crate::FixedPoint32::<4>::from_mantissa(15_000);
+    assert_eq!(
+        small.rpow(3, RoundMode::Unnecessary)?,
+        crate::FixedPoint32::from_mantissa(33_750)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn logarithms_and_exp() -> Result<()> {
+    check_rounding(
+        FixedPoint::ln,
+        "2",
+        ["0.69314718", "0.693147181", "0.693147181"],
+    )?;
+    check_rounding(
+        FixedPoint::ln,
+        "0.5",
+        ["-0.693147181", "-0.693147181", "-0.69314718"],
+    )?;
+    check_rounding(
+        FixedPoint::ln,
+        "0.000000001",
+        ["-20.723265837", "-20.723265837", "-20.723265836"],
+    )?;
+    check_rounding(
+        FixedPoint::ln,
+        "9223372036.854775807",
+        ["22.945006538", "22.945006538", "22.945006539"],
+    )?;
+    check_rounding(
+        FixedPoint::ln,
+        "1.000000001",
+        ["0", "0.000000001", "0.000000001"],
+    )?;
+    check_rounding(
+        FixedPoint::exp,
+        "1",
+        ["2.718281828", "2.718281828", "2.718281829"],
+    )?;
+    check_rounding(
+        FixedPoint::exp,
+        "-1",
+        ["0.367879441", "0.367879441", "0.367879442"],
+    )?;
+    check_rounding(
+        FixedPoint::exp,
+        "21.5",
+        [
+            "2174359553.576488545",
+            "2174359553.576488545",
+            "2174359553.576488546",
+        ],
+    )?;
+    check_rounding(
+        FixedPoint::exp,
+        "-20",
+        ["0.000000002", "0.000000002", "0.000000003"],
+    )?;
+    check_rounding(
+        FixedPoint::exp,
+        "0.000000001",
+        ["1.000000001", "1.000000001", "1.000000002"],
+    )?;
+    check_rounding(
+        FixedPoint::exp,
+        "22.9",
+        [
+            "8817462789.571777786",
+            "8817462789.571777786",
+            "8817462789.571777787",
+        ],
+    )?;
+    check_rounding(
+        FixedPoint::log10,
+        "2",
+        ["0.301029995", "0.301029996", "0.301029996"],
+    )?;
+    check_rounding(
+        FixedPoint::log10,
+        "0.3",
+        ["-0.522878746", "-0.522878745", "-0.522878745"],
+    )?;
+    check_rounding(
+        FixedPoint::log2,
+        "10",
+        ["3.321928094", "3.321928095", "3.321928095"],
+    )?;
+    check_rounding(
+        FixedPoint::log2,
+        "0.7",
+        ["-0.514573173", "-0.514573173", "-0.514573172"],
+    )?;
+
+    // Exact cases.
+    assert_eq!(fp("1")?.ln(RoundMode::Unnecessary)?, fp("0")?);
+    assert_eq!(fp("0")?.exp(RoundMode::Unnecessary)?, fp("1")?);
+    assert_eq!(fp("1000")?.log10(RoundMode::Unnecessary)?, fp("3")?);
+    assert_eq!(fp("0.001")?.log10(RoundMode::Unnecessary)?, fp("-3")?);
+    assert_eq!(fp("1")?.log10(RoundMode::Unnecessary)?, fp("0")?);
+    assert_eq!(fp("1024")?.log2(RoundMode::Unnecessary)?, fp("10")?);
+    assert_eq!(fp("0.125")?.log2(RoundMode::Unnecessary)?, fp("-3")?);
+
+    // Tiny and huge results.
+    assert_eq!(fp("-60")?.exp(RoundMode::HalfEven)?, fp("0")?);
+    assert_eq!(fp("-60")?.exp(RoundMode::Ceil)?, FixedPoint::EPSILON);
+    assert_eq!(
+        fp("-60")?.exp(RoundMode::Unnecessary),
+        Err(ArithmeticError::Inexact)
+    );
+    assert_eq!(
+        fp("23")?.exp(RoundMode::Floor),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(
+        fp("100")?.exp(RoundMode::Floor),
+        Err(ArithmeticError::Overflow)
+    );
+
+    for &x in &["0", "-1"] {
+        for f in &[FixedPoint::ln, FixedPoint::log10, FixedPoint::log2] {
+            assert_eq!(
+                f(fp(x)?, RoundMode::Floor),
+                Err(ArithmeticError::DomainViolation)
+            );
+        }
+    }
+
+    // Results never differ from the exact value by more than rounding does.
+    let mut mantissa = 1i64;
+    while mantissa < i64::MAX / 3 {
+        let x = FixedPoint::from_mantissa(mantissa);
+        let floor = x.ln(RoundMode::Floor)?;
+        assert_eq!(x.ln(RoundMode::Ceil)?, floor.cadd(FixedPoint::EPSILON)?);
+        mantissa = mantissa * 3 + 1;
+    }
+
+    let high = crate::FixedPoint::<18>::from_mantissa(2_000_000_000_000_000_000);
+    assert_eq!(
+        high.ln(RoundMode::HalfEven)?,
+        crate::FixedPoint::from_mantissa(693_147_180_559_945_309)
+    );
+
+    let int = crate::FixedPoint::<0>::from_mantissa(43);
+    assert_eq!(
+        int.exp(RoundMode::Floor)?.mantissa(),
+        4_727_839_468_229_346_561
+    );
+    assert_eq!(
+        crate::FixedPoint::<0>::MAX.ln(RoundMode::HalfUp)?,
+        int.cadd(crate::FixedPoint::ONE)?
+    );
+
+    let small = crate::FixedPoint32::<4>::from_mantissa(10_000);
+    assert_eq!(
+        small.exp(RoundMode::HalfEven)?,
+        crate::FixedPoint32::from_mantissa(27_183)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fractional_power() -> Result<()> {
+    fn t(x: &str, exp: &str, expected: [&str; 3]) -> Result<()> {
+        let exp = fp(exp)?;
+        check_rounding(|x, mode| x.rpowf(exp, mode), x, expected)
+    }
+
+    t("2", "0.5", ["1.414213562", "1.414213562", "1.414213563"])?;
+    t("1.05", "2.5", ["1.129726321", "1.129726322", "1.129726322"])?;
+    t(
+        "1.5",
+        "-0.25",
+        ["0.903602003", "0.903602004", "0.903602004"],
+    )?;
+    t(
+        "123.456",
+        "1.7",
+        ["3593.939341116", "3593.939341117", "3593.939341117"],
+    )?;
+    t("0.5", "40.5", ["0", "0", "0.000000001"])?;
+
+    // Integer exponents are exact if possible.
+    assert_eq!(fp("2")?.rpowf(fp("3")?, RoundMode::Unnecessary)?, fp("8")?);
+    assert_eq!(
+        fp("-2")?.rpowf(fp("3")?, RoundMode::Unnecessary)?,
+        fp("-8")?
+    );
+    assert_eq!(
+        fp("2")?.rpowf(fp("-2")?, RoundMode::Unnecessary)?,
+        fp("0.25")?
+    );
+    assert_eq!(fp("7")?.rpowf(fp("0")?, RoundMode::Unnecessary)?, fp("1")?);
+    assert_eq!(
+        fp("-1")?.rpowf(fp("3000000001")?, RoundMode::Unnecessary)?,
+        fp("-1")?
+    );
+
+    // Exact fractional powers are detected.
+    for &(x, exp, expected) in &[
+        ("4", "0.5", "2"),
+        ("16", "0.25", "2"),
+        ("0.25", "1.5", "0.125"),
+    ] {
+        assert_eq!(
+            fp(x)?.rpowf(fp(exp)?, RoundMode::Unnecessary)?,
+            fp(expected)?
+        );
+    }
+
+    // Zero, negative values and huge results.
+    assert_eq!(fp("0")?.rpowf(fp("0.5")?, RoundMode::Floor)?, fp("0")?);
+    assert_eq!(
+        fp("0")?.rpowf(fp("-0.5")?, RoundMode::Floor),
+        Err(ArithmeticError::DivisionByZero)
+    );
+    assert_eq!(
+        fp("-8")?.rpowf(fp("0.5")?, RoundMode::Floor),
+        Err(ArithmeticError::DomainViolation)
+    );
+    assert_eq!(
+        fp("10")?.rpowf(fp("10.5")?, RoundMode::Floor),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(
+        fp("2")?.rpowf(fp("4000000000")?, RoundMode::Floor),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(
+        fp("-0.5")?.rpowf(fp("4000000001")?, RoundMode::Floor)?,
+        FixedPoint::EPSILON.cneg()?
+    );
+    assert_eq!(
+        fp("0.5")?.rpowf(fp("4000000000.5")?, RoundMode::HalfEven)?,
+        fp("0")?
+    );
+
+    let percent = crate::FixedPoint32::<4>::from_mantissa(40_000);
+    assert_eq!(
+        percent.rpowf(
+            crate::FixedPoint32::from_mantissa(5_000),
+            RoundMode::HalfEven
+        )?,
+        crate::FixedPoint32::from_mantissa(20_000)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn nth_root() -> Result<()> {
+    fn t(x: &str, n: u32, expected: [&str; 3]) -> Result<()> {
+        check_rounding(|x, mode| x.nth_root(n, mode), x, expected)
+    }
+
+    // Small radicands use Newton's iteration, others are estimated and checked exactly.
+    t("2", 2, ["1.414213562", "1.414213562", "1.414213563"])?;
+    t("2", 3, ["1.259921049", "1.25992105", "1.25992105"])?;
+    t("-2", 3, ["-1.25992105", "-1.25992105", "-1.259921049"])?;
+    t(
+        "9223372036.854775807",
+        3,
+        ["2097.151999999", "2097.152", "2097.152"],
+    )?;
+    t("0.5", 4, ["0.840896415", "0.840896415", "0.840896416"])?;
+    t("1.05", 252, ["1.00019363", "1.000193631", "1.000193631"])?;
+    t("123.456", 7, ["1.989699346", "1.989699347", "1.989699347"])?;
+    t(
+        "-0.000000002",
+        5,
+        ["-0.018205643", "-0.018205642", "-0.018205642"],
+    )?;
+    t("1.000000001", 2, ["1", "1", "1.000000001"])?;
+    t("100", 4, ["3.16227766", "3.16227766", "3.162277661"])?;
+
+    let exact = [
+        ("0", 5, "0"),
+        ("7.5", 1, "7.5"),
+        ("8", 3, "2"),
+        ("-27", 3, "-3"),
+        ("0.001", 3, "0.1"),
+        ("0.000000001", 9, "0.1"),
+        ("1024", 10, "2"),
+        ("8589934592", 33, "2"),
+        ("38.443359375", 9, "1.5"),
+        ("-1", 4_000_000_001, "-1"),
+    ];
+
+    for &(x, n, expected) in &exact {
+        assert_eq!(fp(x)?.nth_root(n, RoundMode::Unnecessary)?, fp(expected)?);
+    }
+
+    assert_eq!(
+        fp("-8")?.nth_root(2, RoundMode::Floor),
+        Err(ArithmeticError::DomainViolation)
+    );
+    assert_eq!(
+        fp("8")?.nth_root(0, RoundMode::Floor),
+        Err(ArithmeticError::DomainViolation)
+    );
+    assert_eq!(
+        FixedPoint::MIN.nth_root(3, RoundMode::Floor)?,
+        fp("-2097.152")?
+    );
+
+    let small = crate::FixedPoint32::<4>::from_mantissa(80_000);
+    assert_eq!(
+        small.nth_root(3, RoundMode::Unnecessary)?,
+        crate::FixedPoint32::from_mantissa(20_000)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn trigonometry() -> Result<()> {
+    fn atan2(y: &str, x: &str, expected: [&str; 3]) -> Result<()> {
+        let x = fp(x)?;
+        check_rounding(|y, mode| y.atan2(x, mode), y, expected)
+    }
+
+    check_rounding(
+        FixedPoint::sin,
+        "1",
+        ["0.841470984", "0.841470985", "0.841470985"],
+    )?;
+    check_rounding(
+        FixedPoint::sin,
+        "-0.5",
+        ["-0.479425539", "-0.479425539", "-0.479425538"],
+    )?;
+    check_rounding(FixedPoint::sin, "3.141592654", ["-0.000000001", "0", "0"])?;
+    check_rounding(
+        FixedPoint::sin,
+        "1000000000",
+        ["0.545843449", "0.545843449", "0.54584345"],
+    )?;
+    check_rounding(
+        FixedPoint::sin,
+        "9223372036.854775807",
+        ["-0.774505497", "-0.774505497", "-0.774505496"],
+    )?;
+    check_rounding(
+        FixedPoint::sin,
+        "-9223372036.854775808",
+        ["0.774505497", "0.774505498", "0.774505498"],
+    )?;
+    check_rounding(
+        FixedPoint::cos,
+        "1",
+        ["0.540302305", "0.540302306", "0.540302306"],
+    )?;
+    check_rounding(
+        FixedPoint::cos,
+        "-2",
+        ["-0.416146837", "-0.416146837", "-0.416146836"],
+    )?;
+    check_rounding(FixedPoint::cos, "1.570796327", ["-0.000000001", "0", "0"])?;
+    check_rounding(
+        FixedPoint::cos,
+        "9223372036.854775807",
+        ["-0.632567179", "-0.632567178", "-0.632567178"],
+    )?;
+
+    atan2("1", "1", ["0.785398163", "0.785398163", "0.785398164"])?;
+    atan2("1", "-1", ["2.35619449", "2.35619449", "2.356194491"])?;
+    atan2("-1", "-1", ["-2.356194491", "-2.35619449", "-2.35619449"])?;
+    atan2("0", "-1", ["3.141592653", "3.141592654", "3.141592654"])?;
+    atan2("1", "0", ["1.570796326", "1.570796327", "1.570796327"])?;
+    atan2("-3", "0", ["-1.570796327", "-1.570796327", "-1.570796326"])?;
+    atan2("0.5", "2", ["0.244978663", "0.244978663", "0.244978664"])?;
+    atan2(
+        "-2",
+        "0.7",
+        ["-1.234121508", "-1.234121507", "-1.234121507"],
+    )?;
+    atan2(
+        "0.000000001",
+        "9223372036.854775807",
+        ["0", "0", "0.000000001"],
+    )?;
+
+    // Exact cases.
+    assert_eq!(fp("0")?.sin(RoundMode::Unnecessary)?, fp("0")?);
+    assert_eq!(fp("0")?.cos(RoundMode::Unnecessary)?, fp("1")?);
+    assert_eq!(fp("0")?.atan2(fp("5")?, RoundMode::Unnecessary)?, fp("0")?);
+    assert_eq!(
+        fp("0")?.atan2(fp("0")?, RoundMode::Floor),
+        Err(ArithmeticError::DomainViolation)
+    );
+
+    assert_eq!(FixedPoint::PI, fp("3.141592654")?);
+    assert_eq!(crate::FixedPoint::<0>::PI.mantissa(), 3);
+    assert_eq!(
+        crate::FixedPoint::<18>::PI.mantissa(),
+        3_141_592_653_589_793_238
+    );
+
+    let high = crate::FixedPoint::<18>::from_mantissa(9_223_372_036_854_775_807);
+    assert_eq!(
+        high.sin(RoundMode::Ceil)?,
+        crate::FixedPoint::from_mantissa(200_047_033_033_763_362)
+    );
+
+    let small = crate::FixedPoint32::<9>::from_mantissa(-1_000_000_000);
+    assert_eq!(
+        crate::FixedPoint32::<9>::ZERO.atan2(small, RoundMode::HalfEven),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(
+        small.sin(RoundMode::HalfEven)?,
+        crate::FixedPoint32::from_mantissa(-841_470_985)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn i256_agrees_with_i128() {
+    use crate::i256::I256;
+
+    let values = [
+        0,
+        1,
+        -1,
+        7,
+        -7,
+        1_000_000_000,
+        -1_000_000_000_000_000_000,
+        i128::from(u64::MAX),
+        -i128::from(u64::MAX),
+        i128::MAX,
+        i128::MIN,
+        i128::MAX / 3,
+        i128::MIN / 5,
+    ];
+
+    for &a in &values {
+        for &b in &values {
+            let (wa, wb) = (I256::from(a), I256::from(b));
+
+            assert_eq!(i128::try_from(wa + wb).ok(), a.checked_add(b));
+            assert_eq!(i128::try_from(wa - wb).ok(), a.checked_sub(b));
+            assert_eq!(i128::try_from(wa * wb).ok(), a.checked_mul(b));
+            assert_eq!(wa.cmp(&wb), a.cmp(&b));
+
+            if b != 0 {
+                assert_eq!(i128::try_from(wa / wb).ok(), a.checked_div(b));
+                assert_eq!(i128::try_from(wa % wb), Ok(a.wrapping_rem(b)));
+            }
+        }
+    }
+
+    let (max, min) = (I256::from(i128::MAX), I256::from(i128::MIN));
+    assert_eq!((max * max) / max, max);
+    assert_eq!((max * min) / min, max);
+    assert_eq!((max * max + I256::from(5)) % max, I256::from(5));
+    assert_eq!(-(max * min) / max, -min);
+    assert!(max * min < I256::from(0));
+    assert!(min * min > max * max);
+}
+
+#[test]
+fn fixed_point_128() -> Result<()> {
+    type Wei = crate::FixedPoint128<18>;
+
+    fn wei(s: &str) -> Result<Wei> {
+        Wei::from_str(s).map_err(From::from)
+    }
+
+    let a = wei("123456789012345678.123456789012345678")?;
+    assert_eq!(
+        a.mantissa(),
+        123_456_789_012_345_678_123_456_789_012_345_678
+    );
+    assert_eq!(format!("{}", a), "123456789012345678.123456789012345678");
+    assert_eq!(
+        format!("{}", wei("-0.000000000000000001")?),
+        "-0.000000000000000001"
+    );
+    assert_eq!(wei("170141183460469231731.687303715884105727")?, Wei::MAX);
+    assert_eq!(wei("-170141183460469231731.687303715884105728")?, Wei::MIN);
+    assert!(wei("170141183460469231731.687303715884105728").is_err());
+    assert!(wei("0.0000000000000000001").is_err());
+
+    // The product doesn't fit into 128 bits before rescaling.
+    let b = wei("1000000.000000000000000001")?;
+    assert_eq!(
+        b.rmul(b, RoundMode::Floor)?,
+        wei("1000000000000.000000000002")?
+    );
+    assert_eq!(
+        b.rmul(b, RoundMode::Ceil)?,
+        wei("1000000000000.000000000002000001")?
+    );
+    assert_eq!(
+        wei("-1000000.000000000000000001")?.rmul(b, RoundMode::Floor)?,
+        wei("-1000000000000.000000000002000001")?
+    );
+    assert_eq!(a.rmul(a, RoundMode::Ceil), Err(ArithmeticError::Overflow));
+
+    let three = Wei::from(3);
+    assert_eq!(
+        Wei::ONE.rdiv(three, RoundMode::Floor)?,
+        wei("0.333333333333333333")?
+    );
+    assert_eq!(
+        Wei::ONE.rdiv(three, RoundMode::Ceil)?,
+        wei("0.333333333333333334")?
+    );
+    assert_eq!(
+        Wei::MIN.rdiv(wei("-0.5")?, RoundMode::Ceil),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(Wei::MAX.rdiv(Wei::MAX, RoundMode::Floor)?, Wei::ONE);
+    assert_eq!(
+        wei("0.000000000000000005")?.rdiv(wei("-2")?, RoundMode::HalfEven)?,
+        wei("-0.000000000000000002")?
+    );
+    assert_eq!(
+        wei("1.000000000000000005")?.rmul(wei("0.1")?, RoundMode::HalfUp)?,
+        wei("0.100000000000000001")?
+    );
+    assert_eq!(
+        Wei::ONE.rdiv(Wei::ZERO, RoundMode::Floor),
+        Err(ArithmeticError::DivisionByZero)
+    );
+    assert_eq!(
+        wei("-7")?.rdiv(3, RoundMode::Floor)?,
+        wei("-2.333333333333333334")?
+    );
+
+    assert_eq!(Wei::MAX.cadd(Wei::EPSILON), Err(ArithmeticError::Overflow));
+    assert_eq!(Wei::MIN.csub(Wei::EPSILON), Err(ArithmeticError::Overflow));
+    assert_eq!(Wei::from(2).cmul(3)?, Wei::from(6));
+    assert_eq!(
+        Wei::from(i64::MAX).mantissa(),
+        i128::from(i64::MAX) * 10i128.pow(18)
+    );
+    assert_eq!(i128::try_from(Wei::from(-42)).ok(), Some(-42));
+
+    assert_eq!(wei("12345.6")?.next_power_of_ten()?, wei("100000")?);
+    assert_eq!(Wei::ZERO.next_power_of_ten()?, Wei::EPSILON);
+    assert_eq!(
+        wei("-100000000000000000000.000000000000000001")?.next_power_of_ten(),
+        Err(ArithmeticError::Overflow)
+    );
+
+    assert_eq!(Wei::rounding_from_f64(1.5, RoundMode::HalfUp)?, wei("1.5")?);
+    assert_eq!(wei("-14.25")?.to_f64(), -14.25);
+    assert_eq!(Wei::HALF.rounding_to_i128(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn fixed_point_32() -> Result<()> {
+    type Percent = crate::FixedPoint32<4>;
+
+    fn pc(s: &str) -> Result<Percent> {
+        Percent::from_str(s).map_err(From::from)
+    }
+
+    assert_eq!(std::mem::size_of::<Percent>(), 4);
+
+    let a = pc("199999.9999")?;
+    assert_eq!(a.mantissa(), 1_999_999_999);
+    assert_eq!(format!("{}", a), "199999.9999");
+    assert_eq!(pc("214748.3647")?, Percent::MAX);
+    assert!(pc("214748.3648").is_err());
+    assert!(pc("0.00001").is_err());
+
+    let b = pc("12.5")?;
+    let c = pc("0.0003")?;
+    assert_eq!(b.rmul(c, RoundMode::Floor)?, pc("0.0037")?);
+    assert_eq!(b.rmul(c, RoundMode::Ceil)?, pc("0.0038")?);
+    assert_eq!(b.cneg()?.rmul(c, RoundMode::Floor)?, pc("-0.0038")?);
+    assert_eq!(a.rmul(b, RoundMode::Ceil), Err(ArithmeticError::Overflow));
+    assert_eq!(Percent::ONE.rdiv(pc("3")?, RoundMode::Ceil)?, pc("0.3334")?);
+    assert_eq!(b.rdiv(-3, RoundMode::Floor)?, pc("-4.1667")?);
+    assert_eq!(
+        Percent::MAX.rdiv(pc("0.5")?, RoundMode::Floor),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(
+        Percent::MAX.cadd(Percent::EPSILON),
+        Err(ArithmeticError::Overflow)
+    );
+    assert_eq!(b.cmul(2)?, pc("25")?);
+    assert_eq!(pc("99.5")?.next_power_of_ten()?, pc("100")?);
+    assert_eq!(pc("12.5")?.rounding_to_i32(), 13);
+
+    let wide = crate::FixedPoint::<4>::from(a);
+    assert_eq!(wide.mantissa(), 1_999_999_999);
+    assert_eq!(Percent::try_from(wide)?, a);
+    let too_wide = crate::FixedPoint::<4>::try_from(1_000_000i64)?;
+    assert_eq!(Percent::try_from(too_wide), Err(ConvertError::Overflow));
+
+    Ok(())
 }
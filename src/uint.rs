@@ -0,0 +1,87 @@
+//! Unsigned fixed-width integer used to check roots exactly.
+//!
+//! Only operations required by `nth_root` are implemented, all of them detect overflow.
+
+use core::cmp::Ordering;
+
+/// Little-endian limbs.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct Uint<const LIMBS: usize>([u64; LIMBS]);
+
+impl<const LIMBS: usize> Uint<LIMBS> {
+    pub(crate) fn from_u128(value: u128) -> Uint<LIMBS> {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = value as u64;
+        limbs[1] = (value >> 64) as u64;
+        Uint(limbs)
+    }
+
+    /// Returns the number of limbs without leading zero limbs.
+    fn len(&self) -> usize {
+        self.0
+            .iter()
+            .rposition(|&limb| limb != 0)
+            .map_or(0, |i| i + 1)
+    }
+
+    pub(crate) fn checked_mul(&self, rhs: &Uint<LIMBS>) -> Option<Uint<LIMBS>> {
+        let (len, rhs_len) = (self.len(), rhs.len());
+
+        // The product of nonzero values has at least `len + rhs_len - 1` limbs.
+        if len + rhs_len > LIMBS + 1 {
+            return None;
+        }
+
+        let mut result = [0; LIMBS];
+
+        for i in 0..len {
+            let mut carry = 0;
+
+            for j in 0..rhs_len {
+                let product = u128::from(self.0[i]) * u128::from(rhs.0[j])
+                    + u128::from(result[i + j])
+                    + u128::from(carry);
+                result[i + j] = product as u64;
+                carry = (product >> 64) as u64;
+            }
+
+            if carry != 0 {
+                *result.get_mut(i + rhs_len)? = carry;
+            }
+        }
+
+        Some(Uint(result))
+    }
+
+    pub(crate) fn checked_pow(&self, mut exp: u32) -> Option<Uint<LIMBS>> {
+        let mut base = *self;
+        let mut result = Uint::from_u128(1);
+
+        while exp > 0 {
+            if exp % 2 == 1 {
+                result = result.checked_mul(&base)?;
+            }
+
+            exp /= 2;
+
+            // Squares beyond the highest bit of `exp` aren't computed, so they can't overflow.
+            if exp > 0 {
+                base = base.checked_mul(&base)?;
+            }
+        }
+
+        Some(result)
+    }
+}
+
+impl<const LIMBS: usize> Ord for Uint<LIMBS> {
+    fn cmp(&self, other: &Uint<LIMBS>) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl<const LIMBS: usize> PartialOrd for Uint<LIMBS> {
+    fn partial_cmp(&self, other: &Uint<LIMBS>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}